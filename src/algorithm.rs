@@ -0,0 +1,663 @@
+//! Move sequences and a parser for the common algorithm notation.
+use crate::moves::MoveParseError;
+use crate::symmetry::Plane;
+use crate::{CenterMap, Cube, Move, Symmetry};
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::{Mul, MulAssign, Range};
+use std::str::FromStr;
+
+/// A sequence of moves.
+///
+/// Algorithms parse from the notation found in most algorithm databases:
+///
+/// - moves as accepted by [Move::from_str], separated by whitespace or
+///   written back to back (`R U R' U'` or `RUR'U'`),
+/// - parentheses for grouping, with an optional repetition count and
+///   inverse suffix (`(R U R' U')3`, `(R U)'`),
+/// - commutators `[A, B]` meaning `A B A' B'`,
+/// - conjugates `[A: B]` meaning `A B A'`,
+/// - line comments starting with `//`.
+///
+/// Brackets nest and can be repeated or inverted like groups. The structure
+/// is expanded while parsing, so an algorithm is always a flat sequence and
+/// formats back as plain moves.
+///
+/// # Example
+/// ```
+/// use cubie::{Algorithm, Cube, Move::*};
+///
+/// let sexy: Algorithm = "[R, U]".parse().unwrap();
+/// assert_eq!(sexy.moves(), &[R1, U1, R3, U3]);
+/// assert_eq!(sexy.to_string(), "R U R' U'");
+///
+/// let cube = Cube::default() * &"(R U R' U')6 // identity".parse::<Algorithm>().unwrap();
+/// assert!(cube.is_solved());
+/// ```
+#[derive(Clone, Default, Debug, PartialEq, Eq, Hash)]
+pub struct Algorithm {
+    moves: Vec<Move>,
+}
+
+impl Algorithm {
+    #[inline]
+    pub fn new(moves: Vec<Move>) -> Algorithm {
+        Algorithm { moves }
+    }
+    #[inline]
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+    #[inline]
+    pub fn into_moves(self) -> Vec<Move> {
+        self.moves
+    }
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+    #[inline]
+    pub fn push(&mut self, mv: Move) {
+        self.moves.push(mv);
+    }
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, Move> {
+        self.moves.iter()
+    }
+
+    /// The algorithm undoing this one: the moves reversed and each inverted.
+    ///
+    /// ```
+    /// use cubie::{Algorithm, Cube};
+    /// let alg: Algorithm = "[F: [R, U]]".parse().unwrap();
+    /// assert!((Cube::from(&alg) * &alg.inverse()).is_solved());
+    /// ```
+    pub fn inverse(&self) -> Algorithm {
+        self.moves.iter().rev().map(|mv| mv.inverse()).collect()
+    }
+
+    /// The algorithm with cancelling moves removed and consecutive moves on
+    /// the same axis merged, see [simplify](crate::moves::simplify).
+    ///
+    /// ```
+    /// use cubie::Algorithm;
+    /// let alg: Algorithm = "R U R' R' U2 U'".parse().unwrap();
+    /// assert_eq!(alg.simplified().to_string(), "R U R2 U");
+    /// ```
+    pub fn simplified(&self) -> Algorithm {
+        crate::moves::simplify(&self.moves).into()
+    }
+
+    /// The algorithm seen through a symmetry, each move conjugated with
+    /// [Move::conjugate]. Its state is the [conjugate](Cube::conjugate) of
+    /// the state of `self`.
+    pub fn conjugate(&self, sym: Symmetry) -> Algorithm {
+        self.moves.iter().map(|mv| mv.conjugate(sym)).collect()
+    }
+
+    /// The algorithm reflected through a plane, e.g. a right-hand algorithm
+    /// turned into its left-hand version by the [Plane::M] mirror.
+    ///
+    /// ```
+    /// use cubie::symmetry::Plane;
+    /// use cubie::Algorithm;
+    /// let sune: Algorithm = "R U R' U R U2 R'".parse().unwrap();
+    /// assert_eq!(sune.mirror(Plane::M).to_string(), "L' U' L U' L' U2 L");
+    /// assert_eq!(sune.mirror(Plane::E).to_string(), "R' D' R D' R' D2 R");
+    /// ```
+    pub fn mirror(&self, plane: Plane) -> Algorithm {
+        self.conjugate(plane.symmetry())
+    }
+
+    /// The algorithm performed from another angle, with every move
+    /// [projected](Move::projection) through the rotation.
+    ///
+    /// ```
+    /// use cubie::{Algorithm, Move};
+    /// let alg: Algorithm = "R U R'".parse().unwrap();
+    /// assert_eq!(alg.rotate(Move::Y1.centers()).to_string(), "F U F'");
+    /// ```
+    pub fn rotate(&self, rotation: CenterMap) -> Algorithm {
+        self.conjugate(Symmetry::new(rotation, false))
+    }
+
+    /// The state reached by applying the algorithm to a solved cube.
+    #[inline]
+    pub fn cube(&self) -> Cube {
+        Cube::from(&self.moves)
+    }
+}
+
+impl AsRef<[Move]> for Algorithm {
+    #[inline]
+    fn as_ref(&self) -> &[Move] {
+        &self.moves
+    }
+}
+
+impl From<Vec<Move>> for Algorithm {
+    #[inline]
+    fn from(moves: Vec<Move>) -> Algorithm {
+        Algorithm { moves }
+    }
+}
+
+impl From<&[Move]> for Algorithm {
+    #[inline]
+    fn from(moves: &[Move]) -> Algorithm {
+        Algorithm {
+            moves: moves.to_vec(),
+        }
+    }
+}
+
+impl FromIterator<Move> for Algorithm {
+    fn from_iter<I: IntoIterator<Item = Move>>(iter: I) -> Self {
+        Algorithm {
+            moves: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Move> for Algorithm {
+    fn extend<I: IntoIterator<Item = Move>>(&mut self, iter: I) {
+        self.moves.extend(iter);
+    }
+}
+
+impl IntoIterator for Algorithm {
+    type Item = Move;
+    type IntoIter = std::vec::IntoIter<Move>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.moves.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Algorithm {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.moves.iter()
+    }
+}
+
+/// Concatenation: `a * b` applies `a` and then `b`.
+impl Mul<&Algorithm> for Algorithm {
+    type Output = Algorithm;
+    fn mul(mut self, alg: &Algorithm) -> Algorithm {
+        self *= alg;
+        self
+    }
+}
+
+impl Mul for Algorithm {
+    type Output = Algorithm;
+    fn mul(self, alg: Algorithm) -> Algorithm {
+        self * &alg
+    }
+}
+
+impl MulAssign<&Algorithm> for Algorithm {
+    fn mul_assign(&mut self, alg: &Algorithm) {
+        self.moves.extend_from_slice(&alg.moves);
+    }
+}
+
+impl Mul<&Algorithm> for Cube {
+    type Output = Cube;
+    fn mul(mut self, alg: &Algorithm) -> Cube {
+        self *= alg;
+        self
+    }
+}
+
+impl Mul<Algorithm> for Cube {
+    type Output = Cube;
+    fn mul(self, alg: Algorithm) -> Cube {
+        self * &alg
+    }
+}
+
+impl MulAssign<&Algorithm> for Cube {
+    fn mul_assign(&mut self, alg: &Algorithm) {
+        for &mv in &alg.moves {
+            *self *= mv;
+        }
+    }
+}
+
+/// Formats the moves separated by single spaces.
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, mv) in self.moves.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", mv)?;
+        }
+        Ok(())
+    }
+}
+
+/// One of the four algorithms of an [AlgFamily].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Variant {
+    Original,
+    Inverse,
+    Mirror,
+    InverseMirror,
+}
+
+impl Variant {
+    /// All the variants, in the order [AlgFamily::variants] yields them.
+    pub const ALL: [Variant; 4] = [
+        Variant::Original,
+        Variant::Inverse,
+        Variant::Mirror,
+        Variant::InverseMirror,
+    ];
+}
+
+/// An algorithm together with its inverse, its mirror and the inverse of its
+/// mirror, the variants usually listed together on algorithm sheets.
+///
+/// # Example
+/// ```
+/// use cubie::algorithm::{AlgFamily, Variant};
+/// use cubie::symmetry::Plane;
+///
+/// let sune = "R U R' U R U2 R'".parse().unwrap();
+/// let family = AlgFamily::new(sune, Plane::M);
+/// assert_eq!(family.get(Variant::InverseMirror).to_string(), "L' U2 L U L' U L");
+/// for (variant, alg, cube) in family.variants() {
+///     assert_eq!(alg, family.get(variant));
+///     assert_eq!(alg.cube(), cube);
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AlgFamily {
+    algorithm: Algorithm,
+    plane: Plane,
+}
+
+impl AlgFamily {
+    /// The family of `algorithm`, mirrored through `plane`.
+    pub fn new(algorithm: Algorithm, plane: Plane) -> AlgFamily {
+        AlgFamily { algorithm, plane }
+    }
+
+    #[inline]
+    pub fn algorithm(&self) -> &Algorithm {
+        &self.algorithm
+    }
+
+    #[inline]
+    pub fn plane(&self) -> Plane {
+        self.plane
+    }
+
+    /// The algorithm of one variant.
+    pub fn get(&self, variant: Variant) -> Algorithm {
+        match variant {
+            Variant::Original => self.algorithm.clone(),
+            Variant::Inverse => self.algorithm.inverse(),
+            Variant::Mirror => self.algorithm.mirror(self.plane),
+            Variant::InverseMirror => self.algorithm.mirror(self.plane).inverse(),
+        }
+    }
+
+    /// The four variants with their algorithms and the states they produce
+    /// from a solved cube.
+    pub fn variants(&self) -> impl Iterator<Item = (Variant, Algorithm, Cube)> + '_ {
+        let sym = self.plane.symmetry();
+        let cube = self.algorithm.cube();
+        Variant::ALL.iter().map(move |&variant| {
+            let state = match variant {
+                Variant::Original => cube,
+                Variant::Inverse => cube.inverse(),
+                Variant::Mirror => cube.conjugate(sym),
+                Variant::InverseMirror => cube.conjugate(sym).inverse(),
+            };
+            (variant, self.get(variant), state)
+        })
+    }
+}
+
+/// The largest repetition count of a group.
+const MAX_REPETITION: usize = 1000;
+/// The most moves an algorithm expands to, so that nested repetitions of
+/// untrusted input cannot exhaust memory.
+const MAX_MOVES: usize = 100_000;
+/// The deepest nesting of groups, so that the recursive parser cannot
+/// overflow the stack.
+const MAX_DEPTH: usize = 100;
+
+/// The reason an algorithm failed to parse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlgorithmParseErrorKind {
+    /// A move token was not understood.
+    Move(MoveParseError),
+    /// A character that cannot appear at this point.
+    UnexpectedCharacter,
+    /// A bracket or parenthesis that is never closed.
+    Unclosed,
+    /// A bracket without the `,` or `:` of a commutator or conjugate.
+    MissingSeparator,
+    /// A repetition count above 1000.
+    InvalidRepetition,
+    /// A group expanding the algorithm past 100,000 moves.
+    TooLong,
+    /// Groups nested more than 100 deep.
+    TooDeep,
+}
+
+/// An error produced while parsing an [Algorithm], with the byte range of
+/// the input it refers to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AlgorithmParseError {
+    pub kind: AlgorithmParseErrorKind,
+    pub span: Range<usize>,
+}
+
+impl fmt::Display for AlgorithmParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use AlgorithmParseErrorKind::*;
+        match self.kind {
+            Move(err) => write!(f, "{}", err)?,
+            UnexpectedCharacter => f.write_str("unexpected character")?,
+            Unclosed => f.write_str("unclosed bracket")?,
+            MissingSeparator => f.write_str("expected `,` or `:` in brackets")?,
+            InvalidRepetition => f.write_str("invalid repetition count")?,
+            TooLong => f.write_str("algorithm too long")?,
+            TooDeep => f.write_str("groups nested too deeply")?,
+        }
+        write!(f, " at {}..{}", self.span.start, self.span.end)
+    }
+}
+
+impl std::error::Error for AlgorithmParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            AlgorithmParseErrorKind::Move(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+fn unclosed(open: usize) -> AlgorithmParseError {
+    AlgorithmParseError {
+        kind: AlgorithmParseErrorKind::Unclosed,
+        span: open..open + 1,
+    }
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, kind: AlgorithmParseErrorKind, start: usize) -> AlgorithmParseError {
+        let end = match self.src[start..].chars().next() {
+            Some(c) if start == self.pos => start + c.len_utf8(),
+            _ => self.pos.max(start),
+        };
+        AlgorithmParseError {
+            kind,
+            span: start..end,
+        }
+    }
+
+    /// Skips whitespace and comments, then returns the next character.
+    fn peek(&mut self) -> Option<char> {
+        loop {
+            let rest = &self.src[self.pos..];
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else {
+                return trimmed.chars().next();
+            }
+        }
+    }
+
+    fn next_byte_is(&self, pred: impl Fn(u8) -> bool) -> bool {
+        self.src.as_bytes().get(self.pos).is_some_and(|&b| pred(b))
+    }
+
+    fn sequence(&mut self) -> Result<Vec<Move>, AlgorithmParseError> {
+        let mut moves = Vec::new();
+        loop {
+            let start = match self.peek() {
+                None | Some(')') | Some(']') | Some(',') | Some(':') => return Ok(moves),
+                Some(_) => self.pos,
+            };
+            match self.src.as_bytes()[start] {
+                b'(' => {
+                    self.open(start)?;
+                    let inner = self.sequence()?;
+                    self.close(')', start)?;
+                    self.suffix(&mut moves, inner)?;
+                }
+                b'[' => {
+                    self.open(start)?;
+                    let a = self.sequence()?;
+                    let separator = self.peek();
+                    if separator != Some(',') && separator != Some(':') {
+                        return Err(match separator {
+                            None => unclosed(start),
+                            Some(_) => {
+                                self.error(AlgorithmParseErrorKind::MissingSeparator, self.pos)
+                            }
+                        });
+                    }
+                    self.pos += 1;
+                    let b = self.sequence()?;
+                    self.close(']', start)?;
+                    let mut inner = a.clone();
+                    inner.extend(&b);
+                    inner.extend(a.iter().rev().map(|mv| mv.inverse()));
+                    if separator == Some(',') {
+                        inner.extend(b.iter().rev().map(|mv| mv.inverse()));
+                    }
+                    self.suffix(&mut moves, inner)?;
+                }
+                b if b.is_ascii_alphabetic() => moves.push(self.single_move()?),
+                _ => return Err(self.error(AlgorithmParseErrorKind::UnexpectedCharacter, start)),
+            }
+        }
+    }
+
+    fn open(&mut self, start: usize) -> Result<(), AlgorithmParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(AlgorithmParseErrorKind::TooDeep, start));
+        }
+        self.depth += 1;
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn close(&mut self, bracket: char, open: usize) -> Result<(), AlgorithmParseError> {
+        match self.peek() {
+            Some(c) if c == bracket => {
+                self.depth -= 1;
+                self.pos += 1;
+                Ok(())
+            }
+            None => Err(unclosed(open)),
+            Some(_) => Err(self.error(AlgorithmParseErrorKind::UnexpectedCharacter, self.pos)),
+        }
+    }
+
+    /// Appends a group, applying a directly following repetition count and
+    /// inverse mark.
+    fn suffix(
+        &mut self,
+        moves: &mut Vec<Move>,
+        group: Vec<Move>,
+    ) -> Result<(), AlgorithmParseError> {
+        let start = self.pos;
+        while self.next_byte_is(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let count = if self.pos == start {
+            1
+        } else {
+            self.src[start..self.pos]
+                .parse::<usize>()
+                .ok()
+                .filter(|&count| count <= MAX_REPETITION)
+                .ok_or_else(|| self.error(AlgorithmParseErrorKind::InvalidRepetition, start))?
+        };
+        // Nested groups multiply their counts, so the length is bounded too.
+        if group.len() * count > MAX_MOVES - moves.len().min(MAX_MOVES) {
+            return Err(self.error(AlgorithmParseErrorKind::TooLong, start));
+        }
+        let group = if self.next_byte_is(|b| b == b'\'') {
+            self.pos += 1;
+            group.iter().rev().map(|mv| mv.inverse()).collect()
+        } else {
+            group
+        };
+        for _ in 0..count {
+            moves.extend_from_slice(&group);
+        }
+        Ok(())
+    }
+
+    fn single_move(&mut self) -> Result<Move, AlgorithmParseError> {
+        let start = self.pos;
+        self.pos += 1;
+        if self.next_byte_is(|b| b == b'w') {
+            self.pos += 1;
+        }
+        while self.next_byte_is(|b| b.is_ascii_digit() || b == b'\'') {
+            self.pos += 1;
+        }
+        self.src[start..self.pos]
+            .parse()
+            .map_err(|err| self.error(AlgorithmParseErrorKind::Move(err), start))
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = AlgorithmParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            src: s,
+            pos: 0,
+            depth: 0,
+        };
+        let moves = parser.sequence()?;
+        if parser.peek().is_some() {
+            return Err(parser.error(AlgorithmParseErrorKind::UnexpectedCharacter, parser.pos));
+        }
+        Ok(Algorithm { moves })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Move::*;
+
+    fn parse(s: &str) -> Vec<Move> {
+        s.parse::<Algorithm>().unwrap().into_moves()
+    }
+
+    #[test]
+    fn notation() {
+        assert_eq!(parse("R U R' U'"), vec![R1, U1, R3, U3]);
+        assert_eq!(parse("RUR'U'"), vec![R1, U1, R3, U3]);
+        assert_eq!(parse("[R, U]"), vec![R1, U1, R3, U3]);
+        assert_eq!(parse("[R: U]"), vec![R1, U1, R3]);
+        assert_eq!(parse("[F: [R, U]]"), vec![F1, R1, U1, R3, U3, F3]);
+        assert_eq!(parse("(R U)3"), vec![R1, U1, R1, U1, R1, U1]);
+        assert_eq!(parse("(R U)'"), vec![U3, R3]);
+        assert_eq!(parse("[R, U]2'"), parse("U R U' R' U R U' R'"));
+        assert_eq!(
+            parse("Rw2' x y' M2 // comment\n E"),
+            vec![Rw2, X1, Y3, M2, E1]
+        );
+        assert_eq!(parse("  // only a comment"), vec![]);
+        assert_eq!(parse("()0 R"), vec![R1]);
+    }
+
+    #[test]
+    fn round_trips() {
+        let alg: Algorithm = "[F: [R, U]] (M' U)4 Lw2 z".parse().unwrap();
+        assert_eq!(alg.to_string().parse(), Ok(alg.clone()));
+        let cube = Cube::from(&alg);
+        assert_eq!(Cube::default() * &alg, cube);
+        assert!((cube * alg.inverse()).is_solved());
+        let double = alg.clone() * &alg;
+        assert_eq!(double.len(), alg.len() * 2);
+        assert_eq!(double.cube(), cube * cube);
+    }
+
+    #[test]
+    fn transformations() {
+        let alg: Algorithm = "[F: [R, U]] M' E2 Rw y x'".parse().unwrap();
+        let cube = alg.cube();
+        for sym in Symmetry::symmetries() {
+            assert_eq!(alg.conjugate(sym).cube(), cube.conjugate(sym));
+        }
+        for &plane in &[Plane::M, Plane::E, Plane::S] {
+            assert_eq!(alg.mirror(plane).mirror(plane), alg);
+            let family = AlgFamily::new(alg.clone(), plane);
+            for (_, alg, state) in family.variants() {
+                assert_eq!(alg.cube(), state);
+            }
+        }
+        for rotation in Symmetry::rotations().map(Symmetry::rotation) {
+            let rotated = alg.rotate(rotation);
+            let projected: Algorithm = alg.iter().map(|mv| mv.projection(rotation)).collect();
+            assert_eq!(rotated, projected);
+        }
+    }
+
+    #[test]
+    fn error_spans() {
+        use AlgorithmParseErrorKind::*;
+        let err = |s: &str| s.parse::<Algorithm>().unwrap_err();
+        assert_eq!(
+            err("R U Q"),
+            AlgorithmParseError {
+                kind: Move(MoveParseError::UnknownSymbol),
+                span: 4..5
+            }
+        );
+        assert_eq!(err("R U3'").span, 2..5);
+        assert_eq!(err("R (U R").kind, Unclosed);
+        assert_eq!(err("R (U R").span, 2..3);
+        assert_eq!(err("[R U]").kind, MissingSeparator);
+        assert_eq!(err("[R U]").span, 4..5);
+        assert_eq!(err("R U)").span, 3..4);
+        assert_eq!(err("R + U").span, 2..3);
+        assert_eq!(err("R é").span, 2..4);
+        assert_eq!(err("(R)99999999999").kind, InvalidRepetition);
+        assert_eq!(err("(R U)4000000000").kind, InvalidRepetition);
+        assert_eq!(err("(R U)1001").span, 5..9);
+        assert_eq!(err("((((R U)1000)1000)1000)1000").kind, TooLong);
+        assert_eq!(
+            err("[[[[[[R, U]10, U]10, U]10, U]10, U]10, U]").kind,
+            TooLong
+        );
+        let nested = |depth| format!("{}R{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(nested(100).parse::<Algorithm>().unwrap().moves().len(), 1);
+        assert_eq!(err(&nested(101)).kind, TooDeep);
+        assert_eq!(err(&nested(101)).span, 100..101);
+        assert_eq!(err(&nested(100_000)).kind, TooDeep);
+        assert_eq!(err(&"[R, ".repeat(200)).kind, TooDeep);
+        assert_eq!(
+            "(R U)1000".parse::<Algorithm>().unwrap().moves().len(),
+            2000
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Splitting CFOP solves into their steps.
+//!
+//! A solve is replayed move by move, and each step is considered complete
+//! at the first move after which it is solved: the cross, each of the four
+//! F2L pairs, the orientation (OLL) and then the permutation (PLL) of the
+//! last layer. Pieces are compared to the centers, so the cross color is
+//! found whatever the orientation the cube is held in, and rotations in the
+//! solution are handled.
+//!
+//! ```
+//! use cubie::cfop::{reconstruct, Step};
+//! use cubie::moves::Metric;
+//! use cubie::{Algorithm, Face};
+//!
+//! let scramble: Algorithm = "R U R' U' F2".parse().unwrap();
+//! let solution: Algorithm = "F2 U R U' R'".parse().unwrap();
+//! let reconstruction = reconstruct(scramble.moves(), solution.moves());
+//! assert_eq!(reconstruction.cross, Some(Face::Down));
+//! let steps: Vec<_> = reconstruction.segments.iter().map(|s| s.step).collect();
+//! assert_eq!(steps[0], Step::Cross);
+//! assert_eq!(reconstruction.segments[0].count(Metric::Htm), 1);
+//! assert_eq!(steps.last(), Some(&Step::Pll));
+//! ```
+use crate::moves::Metric;
+use crate::{Corner, CornerOrientation, Cube, Edge, EdgeOrientation, Face, FixedCentersCube};
+use crate::{Move, TileMap};
+use std::ops::Range;
+
+/// A step of a CFOP solve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Step {
+    Cross,
+    /// The F2L pairs, numbered from 1 in the order they are solved. When
+    /// several pairs are completed by the same move, the later ones have
+    /// empty segments.
+    Pair(u8),
+    Oll,
+    Pll,
+    /// Moves after the last completed step of an unfinished solve.
+    Unfinished,
+}
+
+/// The moves of a solution spent on one step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub step: Step,
+    /// The position of the moves in the solution.
+    pub range: Range<usize>,
+    pub moves: Vec<Move>,
+}
+
+impl Segment {
+    /// The number of moves of the step, in the given metric. Skipped steps
+    /// have no moves.
+    pub fn count(&self, metric: Metric) -> u32 {
+        metric.count(&self.moves)
+    }
+}
+
+/// A solution split into the steps of CFOP.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reconstruction {
+    /// The color of the cross, named after its center, or `None` if no cross
+    /// is ever solved.
+    pub cross: Option<Face>,
+    /// The steps in order, covering the whole solution.
+    pub segments: Vec<Segment>,
+}
+
+impl Reconstruction {
+    /// The total number of moves of the solution, in the given metric.
+    pub fn count(&self, metric: Metric) -> u32 {
+        self.segments.iter().map(|s| s.count(metric)).sum()
+    }
+}
+
+fn corner_faces(corner: Corner) -> [Face; 3] {
+    [corner.x(), corner.y(), corner.z()]
+}
+
+fn edge_solved(cube: FixedCentersCube, edge: Edge) -> bool {
+    cube.edges().get(edge) == (edge, EdgeOrientation::Identity)
+}
+
+fn corner_solved(cube: FixedCentersCube, corner: Corner) -> bool {
+    cube.corners().get(corner) == (corner, CornerOrientation::Identity)
+}
+
+fn cross_solved(cube: FixedCentersCube, cross: Face) -> bool {
+    Edge::edges()
+        .filter(|edge| {
+            let (a, b) = edge.faces();
+            a == cross || b == cross
+        })
+        .all(|edge| edge_solved(cube, edge))
+}
+
+/// The corner and edge of each F2L slot around the cross.
+fn slots(cross: Face) -> Vec<(Corner, Edge)> {
+    Corner::corners()
+        .filter(|&corner| corner_faces(corner).contains(&cross))
+        .map(|corner| {
+            let sides: Vec<Face> = corner_faces(corner)
+                .iter()
+                .copied()
+                .filter(|&face| face != cross)
+                .collect();
+            let edge = Edge::edges()
+                .find(|edge| {
+                    let (a, b) = edge.faces();
+                    sides.contains(&a) && sides.contains(&b)
+                })
+                .unwrap();
+            (corner, edge)
+        })
+        .collect()
+}
+
+fn solved_pairs(cube: FixedCentersCube, slots: &[(Corner, Edge)]) -> u8 {
+    slots
+        .iter()
+        .filter(|&&(corner, edge)| corner_solved(cube, corner) && edge_solved(cube, edge))
+        .count() as u8
+}
+
+fn last_layer_oriented(cube: FixedCentersCube, cross: Face) -> bool {
+    let last = cross.opposite();
+    let tiles = TileMap::from(cube);
+    let start = last as usize * 9;
+    tiles.as_array()[start..start + 9]
+        .iter()
+        .all(|&tile| tile == Some(last))
+}
+
+/// Splits a solution of a scrambled cube into CFOP steps.
+///
+/// The cross color is the one whose cross is solved first. A step counts
+/// as complete only once all the steps before it are, so every step gets a
+/// segment, possibly empty when it was skipped. If the solution does not
+/// solve the cube, the moves after the last completed step form a final
+/// [Step::Unfinished] segment.
+pub fn reconstruct(scramble: &[Move], solution: &[Move]) -> Reconstruction {
+    let mut states = Vec::with_capacity(solution.len() + 1);
+    let mut cube = Cube::from(scramble);
+    states.push(FixedCentersCube::from(cube));
+    for &mv in solution {
+        cube *= mv;
+        states.push(FixedCentersCube::from(cube));
+    }
+
+    let cross = Face::faces()
+        .filter_map(|face| {
+            let done = states.iter().position(|&s| cross_solved(s, face))?;
+            Some((done, face))
+        })
+        .min_by_key(|&(done, _)| done);
+
+    // The move index at which each step is completed.
+    let mut ends = Vec::new();
+    if let Some((done, face)) = cross {
+        ends.push((Step::Cross, done));
+        let slots = slots(face);
+        let mut pairs = 0;
+        for (end, &state) in states.iter().enumerate().skip(done) {
+            if !cross_solved(state, face) {
+                continue;
+            }
+            let solved = solved_pairs(state, &slots);
+            while pairs < solved {
+                pairs += 1;
+                ends.push((Step::Pair(pairs), end));
+            }
+            if pairs == 4 && solved == 4 && last_layer_oriented(state, face) {
+                ends.push((Step::Oll, end));
+                if let Some(pll) = states[end..].iter().position(|s| s.is_solved()) {
+                    ends.push((Step::Pll, end + pll));
+                }
+                break;
+            }
+        }
+    }
+    if ends.last().map_or(0, |&(_, end)| end) < solution.len() {
+        ends.push((Step::Unfinished, solution.len()));
+    }
+
+    let mut start = 0;
+    let segments = ends
+        .into_iter()
+        .map(|(step, end)| {
+            let range = start..end;
+            start = end;
+            Segment {
+                step,
+                moves: solution[range.clone()].to_vec(),
+                range,
+            }
+        })
+        .collect();
+    Reconstruction {
+        cross: cross.map(|(_, face)| face),
+        segments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Algorithm, Move::*};
+
+    fn parse(s: &str) -> Vec<Move> {
+        s.parse::<Algorithm>().unwrap().into_moves()
+    }
+
+    #[test]
+    fn full_solve() {
+        // The scramble undoes each step in reverse. The rotation after the last
+        // pair is counted with OLL.
+        let steps = [
+            "D R2 D'",
+            "U R U' R'",
+            "U' L' U L",
+            "y U R U' R'",
+            "y2 U' L' U L y",
+            "R U R' U R U2 R'",
+            "R U R' U' R' F R2 U' R' U' R U R' F'",
+        ];
+        let solution: Vec<Move> = steps.iter().flat_map(|s| parse(s)).collect();
+        let scramble = Algorithm::from(solution.clone()).inverse();
+        let reconstruction = reconstruct(scramble.moves(), &solution);
+        assert_eq!(reconstruction.cross, Some(Face::Down));
+        let found: Vec<(Step, usize)> = reconstruction
+            .segments
+            .iter()
+            .map(|s| (s.step, s.moves.len()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Step::Cross, 3),
+                (Step::Pair(1), 4),
+                (Step::Pair(2), 4),
+                (Step::Pair(3), 5),
+                (Step::Pair(4), 5),
+                (Step::Oll, 8),
+                (Step::Pll, 14),
+            ]
+        );
+        assert_eq!(reconstruction.count(Metric::Etm), solution.len() as u32);
+    }
+
+    #[test]
+    fn rotated_cross_and_skips() {
+        // The cross and three pairs are solved from the start, under a
+        // rotation, and the last pair also skips OLL and PLL.
+        let solution = [X2, R1, U1, R3];
+        let scramble = Algorithm::from(&solution[..]).inverse();
+        let reconstruction = reconstruct(scramble.moves(), &solution);
+        let steps: Vec<Step> = reconstruction.segments.iter().map(|s| s.step).collect();
+        assert_eq!(reconstruction.cross, Some(Face::Down));
+        assert_eq!(
+            steps,
+            vec![
+                Step::Cross,
+                Step::Pair(1),
+                Step::Pair(2),
+                Step::Pair(3),
+                Step::Pair(4),
+                Step::Oll,
+                Step::Pll
+            ]
+        );
+        assert_eq!(reconstruction.segments[4].moves, solution);
+
+        let unfinished = reconstruct(&[R1, U1, F1], &[F3]);
+        assert_eq!(unfinished.segments.last().unwrap().step, Step::Unfinished);
+        assert_eq!(unfinished.count(Metric::Htm), 1);
+    }
+}
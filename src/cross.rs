@@ -0,0 +1,278 @@
+//! Optimal solutions to the cross, the first step of CFOP.
+//!
+//! The cross of a color is solved when the four edges sharing that color
+//! are in place and oriented relative to the centers. [CrossSolver] finds
+//! every shortest solution of a cross, using a table of the distance to
+//! solved of all 190,080 positions of the four edges of the down cross.
+//! Crosses of other colors are solved through the same table, by rotating
+//! the cube so the cross lies on the down face.
+//!
+//! ```
+//! use cubie::cross::CrossSolver;
+//! use cubie::{Cube, Face, Move::*};
+//!
+//! let solver = CrossSolver::new();
+//! let cube = Cube::from(&[R1, D2, F3]);
+//! let cross = solver.solve(cube, Face::Down);
+//! assert_eq!(cross.length, 3);
+//! assert_eq!(cross.solutions, vec![vec![F1, D2, R3]]);
+//! ```
+use crate::{Cube, Edge, EdgeMap, Face, FaceMove, FixedCentersCube, Move, Symmetry};
+
+/// The edges of the down cross.
+const CROSS_EDGES: [Edge; 4] = [Edge::LD, Edge::RD, Edge::FD, Edge::BD];
+
+/// The number of positions of the four cross edges: 12 * 11 * 10 * 9
+/// placements times 2^4 orientations.
+const POSITIONS: usize = 190_080;
+
+/// The position and flip of each cross edge.
+type Pieces = [(u8, u8); 4];
+
+fn index(pieces: Pieces) -> usize {
+    let mut permutation = 0;
+    let mut flips = 0;
+    for (i, &(position, flip)) in pieces.iter().enumerate() {
+        let before = pieces[..i].iter().filter(|&&(p, _)| p < position).count();
+        permutation = permutation * (12 - i) + position as usize - before;
+        flips = flips * 2 + flip as usize;
+    }
+    permutation * 16 + flips
+}
+
+fn pieces(index: usize) -> Pieces {
+    let mut pieces = [(0, 0); 4];
+    let mut permutation = index / 16;
+    let mut ranks = [0; 4];
+    for i in (0..4).rev() {
+        ranks[i] = permutation % (12 - i);
+        permutation /= 12 - i;
+        pieces[i].1 = ((index >> (3 - i)) & 1) as u8;
+    }
+    let mut used = 0u16;
+    for (piece, &rank) in pieces.iter_mut().zip(ranks.iter()) {
+        let position = (0..12).filter(|p| used & (1 << p) == 0).nth(rank).unwrap();
+        used |= 1 << position;
+        piece.0 = position;
+    }
+    pieces
+}
+
+fn down_pieces(edges: EdgeMap) -> Pieces {
+    CROSS_EDGES.map(|edge| {
+        let (position, flip) = edges.get(edge);
+        (position as u8, flip as u8)
+    })
+}
+
+/// The rotation bringing the cross of a color to the down face.
+pub(crate) fn down_symmetry(cross: Face) -> Symmetry {
+    Symmetry::rotations()
+        .find(|sym| sym.face(cross) == Face::Down)
+        .unwrap()
+}
+
+/// The shortest solutions of a cross.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrossSolutions {
+    /// The color of the cross, named after its center.
+    pub cross: Face,
+    /// The number of moves of each solution, in HTM.
+    pub length: u8,
+    /// Every solution of `length` moves, without consecutive moves of the
+    /// same face and with moves of opposite faces in a single order. For an
+    /// already solved cross, this is a single empty solution.
+    pub solutions: Vec<Vec<Move>>,
+}
+
+/// A solver for the cross of any color, returning all optimal solutions.
+///
+/// Building the solver computes its distance table, which takes a fraction
+/// of a second. Every cross can be solved in at most 8 moves.
+pub struct CrossSolver {
+    distances: Vec<u8>,
+    /// For each face move, the position and flip each edge position is
+    /// moved to.
+    moves: [[(u8, u8); 12]; 18],
+}
+
+impl Default for CrossSolver {
+    fn default() -> CrossSolver {
+        CrossSolver::new()
+    }
+}
+
+impl CrossSolver {
+    /// Builds the solver and its distance table.
+    pub fn new() -> CrossSolver {
+        let mut moves = [[(0, 0); 12]; 18];
+        for (table, mv) in moves.iter_mut().zip(FaceMove::moves()) {
+            for (entry, edge) in table.iter_mut().zip(Edge::edges()) {
+                let (position, flip) = mv.edges().get(edge);
+                *entry = (position as u8, flip as u8);
+            }
+        }
+        let mut solver = CrossSolver {
+            distances: vec![u8::MAX; POSITIONS],
+            moves,
+        };
+        let solved = index(CROSS_EDGES.map(|edge| (edge as u8, 0)));
+        solver.distances[solved] = 0;
+        let mut frontier = vec![solved];
+        let mut depth = 0;
+        while !frontier.is_empty() {
+            depth += 1;
+            let mut next = Vec::new();
+            for &position in &frontier {
+                for mv in 0..18 {
+                    let moved = index(solver.apply(pieces(position), mv));
+                    if solver.distances[moved] == u8::MAX {
+                        solver.distances[moved] = depth;
+                        next.push(moved);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        solver
+    }
+
+    fn apply(&self, mut pieces: Pieces, mv: usize) -> Pieces {
+        for piece in &mut pieces {
+            let (position, flip) = self.moves[mv][piece.0 as usize];
+            *piece = (position, piece.1 ^ flip);
+        }
+        pieces
+    }
+
+    /// The cross edges of a cube, seen from the down face.
+    fn down_cross(cube: Cube, cross: Face) -> Pieces {
+        let sym = down_symmetry(cross);
+        down_pieces(FixedCentersCube::from(cube).conjugate(sym).edges())
+    }
+
+    /// The number of moves solving the down cross of edges already seen
+    /// from the down face.
+    pub(crate) fn down_distance(&self, edges: EdgeMap) -> u8 {
+        self.distances[index(down_pieces(edges))]
+    }
+
+    /// The number of moves of the shortest solutions of the cross of the
+    /// given color.
+    pub fn distance(&self, cube: Cube, cross: Face) -> u8 {
+        self.distances[index(Self::down_cross(cube, cross))]
+    }
+
+    /// Finds all the shortest solutions of the cross of the given color.
+    ///
+    /// The solutions are made of face turns in the orientation the cube is
+    /// held in, as with [Solver::search](crate::Solver::search).
+    pub fn solve(&self, cube: Cube, cross: Face) -> CrossSolutions {
+        let pieces = Self::down_cross(cube, cross);
+        let length = self.distances[index(pieces)];
+        let mut solutions = Vec::new();
+        self.search(pieces, length, None, &mut Vec::new(), &mut solutions);
+
+        let sym = down_symmetry(cross).inverse();
+        let rotation = cube.centers();
+        for solution in &mut solutions {
+            for mv in solution.iter_mut() {
+                *mv = mv.conjugate(sym).projection(rotation);
+            }
+        }
+        CrossSolutions {
+            cross,
+            length,
+            solutions,
+        }
+    }
+
+    /// Finds the shortest solutions of the cross of the color that can be
+    /// solved in the fewest moves. Ties go to the first color in
+    /// [Face::faces] order.
+    pub fn solve_color_neutral(&self, cube: Cube) -> CrossSolutions {
+        let cross = Face::faces()
+            .min_by_key(|&face| self.distance(cube, face))
+            .unwrap();
+        self.solve(cube, cross)
+    }
+
+    fn search(
+        &self,
+        pieces: Pieces,
+        depth: u8,
+        last: Option<Face>,
+        path: &mut Vec<Move>,
+        solutions: &mut Vec<Vec<Move>>,
+    ) {
+        if depth == 0 {
+            solutions.push(path.clone());
+            return;
+        }
+        for (i, mv) in FaceMove::moves().enumerate() {
+            let face = mv.face();
+            if let Some(last) = last {
+                // Moves of opposite faces commute, keep only one order.
+                if face == last || (face == last.opposite() && (face as u8) < last as u8) {
+                    continue;
+                }
+            }
+            let moved = self.apply(pieces, i);
+            if self.distances[index(moved)] != depth - 1 {
+                continue;
+            }
+            path.push(mv.into());
+            self.search(moved, depth - 1, Some(face), path, solutions);
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EdgeOrientation;
+
+    fn cross_solved(cube: Cube, cross: Face) -> bool {
+        let edges = FixedCentersCube::from(cube).edges();
+        Edge::edges()
+            .filter(|edge| edge.faces().0 == cross || edge.faces().1 == cross)
+            .all(|edge| edges.get(edge) == (edge, EdgeOrientation::Identity))
+    }
+
+    #[test]
+    fn coordinate_round_trips() {
+        for i in (0..POSITIONS).step_by(7) {
+            assert_eq!(index(pieces(i)), i);
+        }
+    }
+
+    #[test]
+    fn distance_table() {
+        let solver = CrossSolver::new();
+        let mut counts = [0; 9];
+        for &distance in &solver.distances {
+            counts[distance as usize] += 1;
+        }
+        assert_eq!(counts, [1, 15, 158, 1394, 9809, 46381, 97254, 34966, 102]);
+    }
+
+    #[test]
+    fn optimal_solutions() {
+        let solver = CrossSolver::new();
+        let mut rng = oorandom::Rand32::new(17);
+        for _ in 0..10 {
+            let cube = Cube::random(&mut rng) * Move::X1;
+            for face in Face::faces() {
+                let cross = solver.solve(cube, face);
+                assert!(!cross.solutions.is_empty());
+                for solution in &cross.solutions {
+                    assert_eq!(solution.len(), cross.length as usize);
+                    assert!(cross_solved(cube * Cube::from(solution), face));
+                }
+            }
+            let best = solver.solve_color_neutral(cube);
+            assert!(Face::faces().all(|face| solver.distance(cube, face) >= best.length));
+        }
+    }
+}
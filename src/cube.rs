@@ -5,11 +5,46 @@ use std::ops::{Mul, MulAssign};
 
 pub mod center;
 pub mod corner;
+mod cycle;
 pub mod edge;
 mod fixed_centers_cube;
+mod singmaster;
+pub use self::cycle::{CornerCycle, EdgeCycle};
 pub use self::edge::Edge;
 pub use self::fixed_centers_cube::FixedCentersCube;
 
+/// A source of uniform random numbers, used to draw random states with
+/// [Cube::random] and [FixedCentersCube::random].
+///
+/// It is implemented for [oorandom::Rand32], and for any closure returning a
+/// number below its argument, so that other generators can be used.
+///
+/// # Example
+/// ```
+/// use cubie::Cube;
+/// let mut rng = oorandom::Rand32::new(1);
+/// assert!(Cube::random(&mut rng).has_solution());
+/// // Any generator can be wrapped in a closure.
+/// let mut draw = |bound| rng.rand_range(0..bound);
+/// assert!(Cube::random(&mut draw).has_solution());
+/// ```
+pub trait RandomSource {
+    /// Draws a number uniformly from `0..bound`, where `bound` is not zero.
+    fn below(&mut self, bound: u32) -> u32;
+}
+
+impl RandomSource for oorandom::Rand32 {
+    fn below(&mut self, bound: u32) -> u32 {
+        self.rand_range(0..bound)
+    }
+}
+
+impl<F: FnMut(u32) -> u32> RandomSource for F {
+    fn below(&mut self, bound: u32) -> u32 {
+        self(bound)
+    }
+}
+
 /// Stores both the centers and corners in a single u64. Used in
 /// cube to optimize for size.
 #[derive(Clone, Copy, Eq, PartialEq, Hash)]
@@ -155,6 +190,55 @@ impl Cube {
             .and_then(|_| self.centers().validate())
     }
 
+    /// Draws a state uniformly at random from every solvable state, including
+    /// each of the 24 whole-cube orientations.
+    ///
+    /// See [FixedCentersCube::random] for the distribution of the pieces.
+    pub fn random(rng: &mut impl RandomSource) -> Cube {
+        let rotation = crate::moves::ROTATION_TABLE[rng.below(24) as usize];
+        Cube::from(FixedCentersCube::random(rng)) * rotation
+    }
+
+    /// The number of solvable states, counting each of the 24 orientations.
+    pub const COUNT: u128 = FixedCentersCube::COUNT * 24;
+
+    /// A dense index of the state in `0..Cube::COUNT`, combining the
+    /// [FixedCentersCube::rank] of the pieces relative to the centers with
+    /// the [CenterMap::index] of the orientation.
+    ///
+    /// The rank of an unsolvable state is unspecified.
+    ///
+    /// # Example
+    /// ```
+    /// use cubie::{Cube, Move::*};
+    /// let cube = Cube::from(&[R1, Uw2, X1]);
+    /// assert_eq!(Cube::unrank(cube.rank()), Some(cube));
+    /// ```
+    pub fn rank(self) -> u128 {
+        FixedCentersCube::from(self).rank() * 24 + self.centers().index() as u128
+    }
+
+    /// The state with the given [rank](Cube::rank), or `None` if
+    /// `rank >= Cube::COUNT`.
+    pub fn unrank(rank: u128) -> Option<Cube> {
+        let rotation = crate::moves::ROTATION_TABLE[(rank % 24) as usize];
+        FixedCentersCube::unrank(rank / 24).map(|fixed| Cube::from(fixed) * rotation)
+    }
+
+    /// The number of times the cube state must be applied to return to the
+    /// identity, including the whole-cube orientation.
+    ///
+    /// # Example
+    /// ```
+    /// use cubie::{Cube, Move::*};
+    /// let sexy = Cube::from(&[R1, U1, R3, U3]);
+    /// assert_eq!(sexy.order(), 6);
+    /// ```
+    pub fn order(self) -> u32 {
+        let order = cycle::lcm(self.corners().order(), self.edges.order());
+        cycle::lcm(order, self.centers().order())
+    }
+
     /// Returns true if the in a solved position. Note: this no the same
     /// as being the identify cube, as rotating a cube solved cube
     /// results in another solved cube.
@@ -406,6 +490,53 @@ mod tests {
             cube *= random_move();
         }
     }
+    #[test]
+    fn random_states_are_solvable() {
+        let mut rng = oorandom::Rand32::new(0xdeadbeef);
+        let mut positions = [[0u32; 12]; 12];
+        let mut rotations = 0u32;
+        for _ in 0..2400 {
+            let cube = Cube::random(&mut rng);
+            assert_eq!(cube.validate(), Ok(()));
+            assert!(cube.has_solution());
+            assert!(FixedCentersCube::from(cube).has_solution());
+            rotations |= 1 << cube.centers().index();
+            let fc = FixedCentersCube::random(&mut rng);
+            assert!(fc.has_solution());
+            for (edge, (pos, _)) in fc.edges().iter() {
+                positions[edge as usize][pos as usize] += 1;
+            }
+        }
+        assert_eq!(rotations.count_ones(), 24);
+        // Each edge should land in each position about 200 times.
+        for counts in positions.iter() {
+            for &count in counts.iter() {
+                assert!(count > 120 && count < 280, "{:?}", positions);
+            }
+        }
+    }
+
+    #[test]
+    fn rank_round_trip() {
+        let mut rng = oorandom::Rand32::new(0x1234);
+        for _ in 0..1000 {
+            let cube = Cube::random(&mut rng);
+            let rank = cube.rank();
+            assert!(rank < Cube::COUNT);
+            assert_eq!(Cube::unrank(rank), Some(cube));
+            let fixed = FixedCentersCube::from(cube);
+            assert_eq!(FixedCentersCube::unrank(fixed.rank()), Some(fixed));
+        }
+        for &rank in &[0, 1, 2047, 2048, FixedCentersCube::COUNT - 1] {
+            let fixed = FixedCentersCube::unrank(rank).unwrap();
+            assert!(fixed.has_solution());
+            assert_eq!(fixed.rank(), rank);
+        }
+        assert_eq!(FixedCentersCube::unrank(FixedCentersCube::COUNT), None);
+        assert_eq!(Cube::unrank(Cube::COUNT), None);
+        assert_eq!(Cube::unrank(Cube::COUNT - 1).unwrap().rank(), Cube::COUNT - 1);
+    }
+
     #[test]
     fn is_rotated_cube_solved() {
         use std::convert::TryFrom;
@@ -0,0 +1,255 @@
+//! Cycle decomposition of piece maps.
+use crate::{CenterMap, Corner, CornerMap, CornerOrientation, Edge, EdgeMap, EdgeOrientation};
+
+/// A cycle of corner pieces, each corner moving to the position of the next,
+/// the last wrapping around to the first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CornerCycle {
+    /// The corners in the cycle, starting from the lowest corner.
+    pub corners: Vec<Corner>,
+    /// The twist each corner has accumulated once it returns to its
+    /// original position.
+    pub twist: CornerOrientation,
+}
+
+impl CornerCycle {
+    /// The number of applications of the cycle before every corner is solved.
+    pub fn order(&self) -> u32 {
+        if self.twist.is_identity() {
+            self.corners.len() as u32
+        } else {
+            self.corners.len() as u32 * 3
+        }
+    }
+}
+
+/// A cycle of edge pieces, each edge moving to the position of the next,
+/// the last wrapping around to the first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EdgeCycle {
+    /// The edges in the cycle, starting from the lowest edge.
+    pub edges: Vec<Edge>,
+    /// The flip each edge has accumulated once it returns to its
+    /// original position.
+    pub flip: EdgeOrientation,
+}
+
+impl EdgeCycle {
+    /// The number of applications of the cycle before every edge is solved.
+    pub fn order(&self) -> u32 {
+        if self.flip.is_identity() {
+            self.edges.len() as u32
+        } else {
+            self.edges.len() as u32 * 2
+        }
+    }
+}
+
+pub(crate) fn lcm(a: u32, b: u32) -> u32 {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        let t = x % y;
+        x = y;
+        y = t;
+    }
+    a / x * b
+}
+
+impl CornerMap {
+    /// Decomposes the corner permutation into disjoint cycles.
+    ///
+    /// Solved corners are omitted, but a corner twisted in place is
+    /// returned as a cycle of length one.
+    ///
+    /// # Example
+    /// ```
+    /// use cubie::{Corner, CornerOrientation, Move::*};
+    /// let cycles = U1.corners().cycles();
+    /// assert_eq!(cycles.len(), 1);
+    /// assert_eq!(cycles[0].corners, vec![Corner::URF, Corner::ULF, Corner::ULB, Corner::URB]);
+    /// assert_eq!(cycles[0].twist, CornerOrientation::Identity);
+    /// ```
+    pub fn cycles(self) -> Vec<CornerCycle> {
+        let mut cycles = Vec::new();
+        let mut visited = 0u32;
+        for start in Corner::corners() {
+            if visited & (1 << start as u32) != 0 {
+                continue;
+            }
+            let mut corners = Vec::new();
+            let mut twist = CornerOrientation::Identity;
+            let mut at = start;
+            loop {
+                visited |= 1 << at as u32;
+                corners.push(at);
+                let (next, ori) = self.get(at);
+                twist *= ori;
+                at = next;
+                if at == start {
+                    break;
+                }
+            }
+            if corners.len() > 1 || !twist.is_identity() {
+                cycles.push(CornerCycle { corners, twist });
+            }
+        }
+        cycles
+    }
+
+    /// Summarizes [CornerMap::cycles] as `(length, twist)` pairs, longest first.
+    pub fn cycle_type(self) -> Vec<(usize, CornerOrientation)> {
+        let mut summary: Vec<_> = self
+            .cycles()
+            .iter()
+            .map(|cycle| (cycle.corners.len(), cycle.twist))
+            .collect();
+        summary.sort_by_key(|&(len, twist)| (std::cmp::Reverse(len), twist as u8));
+        summary
+    }
+
+    /// The smallest number of applications of the map that gives the identity.
+    pub fn order(self) -> u32 {
+        self.cycles()
+            .iter()
+            .fold(1, |order, cycle| lcm(order, cycle.order()))
+    }
+}
+
+impl EdgeMap {
+    /// Decomposes the edge permutation into disjoint cycles.
+    ///
+    /// Solved edges are omitted, but an edge flipped in place is returned
+    /// as a cycle of length one.
+    ///
+    /// # Example
+    /// ```
+    /// use cubie::{Edge, EdgeOrientation, Move::*};
+    /// let cycles = F1.edges().cycles();
+    /// assert_eq!(cycles.len(), 1);
+    /// assert_eq!(cycles[0].edges.len(), 4);
+    /// assert_eq!(cycles[0].flip, EdgeOrientation::Identity);
+    /// ```
+    pub fn cycles(self) -> Vec<EdgeCycle> {
+        let mut cycles = Vec::new();
+        let mut visited = 0u32;
+        for start in Edge::edges() {
+            if visited & (1 << start as u32) != 0 {
+                continue;
+            }
+            let mut edges = Vec::new();
+            let mut flip = 0u8;
+            let mut at = start;
+            loop {
+                visited |= 1 << at as u32;
+                edges.push(at);
+                let (next, ori) = self.get(at);
+                flip ^= ori as u8;
+                at = next;
+                if at == start {
+                    break;
+                }
+            }
+            let flip = if flip == 0 {
+                EdgeOrientation::Identity
+            } else {
+                EdgeOrientation::Flipped
+            };
+            if edges.len() > 1 || flip.is_flipped() {
+                cycles.push(EdgeCycle { edges, flip });
+            }
+        }
+        cycles
+    }
+
+    /// Summarizes [EdgeMap::cycles] as `(length, flip)` pairs, longest first.
+    pub fn cycle_type(self) -> Vec<(usize, EdgeOrientation)> {
+        let mut summary: Vec<_> = self
+            .cycles()
+            .iter()
+            .map(|cycle| (cycle.edges.len(), cycle.flip))
+            .collect();
+        summary.sort_by_key(|&(len, flip)| (std::cmp::Reverse(len), flip as u8));
+        summary
+    }
+
+    /// The smallest number of applications of the map that gives the identity.
+    pub fn order(self) -> u32 {
+        self.cycles()
+            .iter()
+            .fold(1, |order, cycle| lcm(order, cycle.order()))
+    }
+}
+
+impl CenterMap {
+    /// The smallest number of applications of the rotation that gives the
+    /// identity, one of 1, 2, 3 or 4.
+    pub fn order(self) -> u32 {
+        let mut power = self;
+        let mut order = 1;
+        while power != CenterMap::default() {
+            power *= self;
+            order += 1;
+        }
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cube;
+    use crate::Move::*;
+
+    #[test]
+    fn known_orders() {
+        let order = |moves: &[crate::Move]| Cube::from(moves).order();
+        assert_eq!(order(&[]), 1);
+        assert_eq!(order(&[R1]), 4);
+        assert_eq!(order(&[R2]), 2);
+        assert_eq!(order(&[R1, U1]), 105);
+        assert_eq!(order(&[R1, U1, R3, U3]), 6);
+        assert_eq!(order(&[R1, U2, D3, B1, D3]), 1260);
+        assert_eq!(order(&[Y1]), 4);
+        assert_eq!(order(&[M1]), 4);
+        assert_eq!(order(&[R1, X3]), 4);
+    }
+
+    #[test]
+    fn order_matches_repetition() {
+        let mut rng = oorandom::Rand32::new(31);
+        for _ in 0..50 {
+            let cube = Cube::random(&mut rng);
+            let order = cube.order();
+            let mut power = cube;
+            for _ in 1..order {
+                assert!(power != Cube::default());
+                power *= cube;
+            }
+            assert!(power == Cube::default());
+        }
+    }
+
+    #[test]
+    fn cycle_types() {
+        // Sune swaps two pairs of corners, twisting both pairs, and cycles
+        // three edges.
+        let sune = Cube::from(&[R1, U1, R3, U1, R1, U2, R3]);
+        assert_eq!(
+            sune.corners().cycle_type(),
+            vec![(2, CornerOrientation::Cw), (2, CornerOrientation::Ccw)]
+        );
+        assert_eq!(sune.order(), 6);
+        assert_eq!(
+            sune.edges().cycle_type(),
+            vec![(3, EdgeOrientation::Identity)]
+        );
+        let superflip = Cube::from(&[
+            U1, R2, F1, B1, R1, B2, R1, U2, L1, B2, R1, U3, D3, R2, F1, R3, L1, B2, U2, F2,
+        ]);
+        assert_eq!(
+            superflip.edges().cycle_type(),
+            vec![(1, EdgeOrientation::Flipped); 12]
+        );
+        assert!(superflip.corners().cycles().is_empty());
+    }
+}
@@ -268,7 +268,7 @@ impl EdgeMap {
             if pos > 11 {
                 return Err(MapError::OutOfBounds);
             }
-            edge_mask |= 1 << (edge as u32);
+            edge_mask |= 1 << pos;
             flip_sum ^= flip as u32;
         }
         if edge_mask != 0b1111_1111_1111 {
@@ -445,6 +445,13 @@ mod tests {
         }
     }
     #[test]
+    fn duplicate_edge_positions() {
+        // Two edges in the position of the first one, none in the second.
+        let raw = EdgeMap::default().raw & !(0b1111 << 5);
+        assert_eq!(EdgeMap::from_raw(raw), Err(MapError::Duplicate));
+        assert!(EdgeMap::from_raw(EdgeMap::default().raw).is_ok());
+    }
+    #[test]
     fn edge_premutation_index_mapping() {
         let mut rng1 = oorandom::Rand32::new(0xdeadbeef);
         let mut random_move = || -> Move { Move::try_from((rng1.rand_u32() % 18) as u8).unwrap() };
@@ -1,4 +1,5 @@
-use crate::{CornerMap, EdgeMap, FaceMove, MapError};
+use crate::cube::RandomSource;
+use crate::{COIndex, CPIndex, CornerMap, EOIndex, EPIndex, EdgeMap, FaceMove, MapError};
 
 use std::ops::{Mul, MulAssign};
 /// 3x3 Puzzle Cube, with centers fixed in space.
@@ -53,6 +54,88 @@ impl FixedCentersCube {
     pub fn is_solved(self) -> bool {
         self.corners.is_solved() && self.edges.is_solved()
     }
+
+    /// The number of solvable states.
+    pub const COUNT: u128 = 43_252_003_274_489_856_000;
+
+    /// A dense index of the state in `0..FixedCentersCube::COUNT`, composed
+    /// from the permutation and orientation indices of the corners and edges.
+    ///
+    /// The rank of an unsolvable state is unspecified.
+    ///
+    /// # Example
+    /// ```
+    /// use cubie::{FaceMove::*, FixedCentersCube};
+    /// let cube = FixedCentersCube::default() * R1 * U2;
+    /// assert_eq!(FixedCentersCube::unrank(cube.rank()), Some(cube));
+    /// assert_eq!(FixedCentersCube::default().rank(), 0);
+    /// ```
+    pub fn rank(self) -> u128 {
+        let corners = self.corners.permutation_index().0 as u128 * COIndex::SIZE as u128
+            + self.corners.orientation_index().0 as u128;
+        let edges = (corners * (EPIndex::SIZE / 2) as u128
+            + (self.edges.permutation_index().0 >> 1) as u128)
+            * EOIndex::SIZE as u128;
+        edges + self.edges.orientation_index().0 as u128
+    }
+
+    /// The state with the given [rank](FixedCentersCube::rank), or `None` if
+    /// `rank >= FixedCentersCube::COUNT`.
+    pub fn unrank(rank: u128) -> Option<FixedCentersCube> {
+        if rank >= FixedCentersCube::COUNT {
+            return None;
+        }
+        let eo = (rank % EOIndex::SIZE as u128) as u32;
+        let rank = rank / EOIndex::SIZE as u128;
+        let ep = (rank % (EPIndex::SIZE / 2) as u128) as u32;
+        let rank = rank / (EPIndex::SIZE / 2) as u128;
+        let co = (rank % COIndex::SIZE as u128) as u32;
+        let cp = (rank / COIndex::SIZE as u128) as u32;
+
+        let mut corners = CornerMap::default();
+        corners.set_permutation_index(CPIndex(cp));
+        corners.set_orientation_index(COIndex(co));
+        let mut edges = EdgeMap::default();
+        edges.set_orientation_index(EOIndex(eo));
+        edges.set_permutation_index(EPIndex(ep << 1));
+        if edges.permutation_parity() != corners.permutation_parity() {
+            edges.set_permutation_index(EPIndex((ep << 1) | 1));
+        }
+        Some(FixedCentersCube { corners, edges })
+    }
+
+    /// The number of times the state must be applied to return to solved.
+    pub fn order(self) -> u32 {
+        super::cycle::lcm(self.corners.order(), self.edges.order())
+    }
+
+    /// Draws a state uniformly at random from the 43,252,003,274,489,856,000
+    /// solvable states.
+    ///
+    /// Every coordinate is drawn independently, then the edge permutation is
+    /// multiplied by a quarter turn whenever its parity disagrees with the
+    /// corners. That fix-up pairs each unsolvable state with exactly one
+    /// solvable state, so the result stays uniform.
+    ///
+    /// # Example
+    /// ```
+    /// use cubie::FixedCentersCube;
+    /// let mut rng = oorandom::Rand32::new(0xc0ffee);
+    /// let cube = FixedCentersCube::random(&mut rng);
+    /// assert!(cube.has_solution());
+    /// ```
+    pub fn random(rng: &mut impl RandomSource) -> FixedCentersCube {
+        let mut corners = CornerMap::default();
+        corners.set_permutation_index(CPIndex(rng.below(CPIndex::SIZE)));
+        corners.set_orientation_index(COIndex(rng.below(COIndex::SIZE)));
+        let mut edges = EdgeMap::default();
+        edges.set_permutation_index(EPIndex(rng.below(EPIndex::SIZE)));
+        edges.set_orientation_index(EOIndex(rng.below(EOIndex::SIZE)));
+        if edges.permutation_parity() != corners.permutation_parity() {
+            edges *= FaceMove::U1;
+        }
+        FixedCentersCube { corners, edges }
+    }
 }
 
 impl From<FaceMove> for FixedCentersCube {
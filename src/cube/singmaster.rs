@@ -0,0 +1,370 @@
+//! Singmaster piece names and Reid's positional notation.
+//!
+//! Pieces are named by the faces they touch, e.g. `UFR` or `UF`. A state is
+//! written in Reid's format as the piece found at each position, in the
+//! order
+//!
+//! ```text
+//! UF UR UB UL DF DR DB DL FR FL BR BL UFR URB UBL ULF DRF DFL DLB DBR
+//! ```
+//!
+//! The order of the letters of each piece encodes its orientation: the first
+//! letter is the sticker on the first face of the position. At the `UFR`
+//! position, `FRU` is the `UFR` corner twisted so its `U` sticker faces `R`.
+use crate::tile::{tiles_from_corner, tiles_from_edge, TileMapConversionError};
+use crate::{Corner, CornerMap, Edge, EdgeMap, Face, FixedCentersCube, TileMap};
+use std::fmt;
+use std::str::FromStr;
+
+use Face::*;
+
+/// The corner positions and their faces in Reid order.
+const REID_CORNERS: [(Corner, [Face; 3]); 8] = [
+    (Corner::URF, [Up, Front, Right]),
+    (Corner::URB, [Up, Right, Back]),
+    (Corner::ULB, [Up, Back, Left]),
+    (Corner::ULF, [Up, Left, Front]),
+    (Corner::DRF, [Down, Right, Front]),
+    (Corner::DLF, [Down, Front, Left]),
+    (Corner::DLB, [Down, Left, Back]),
+    (Corner::DRB, [Down, Back, Right]),
+];
+
+/// The edge positions and their faces in Reid order.
+const REID_EDGES: [(Edge, [Face; 2]); 12] = [
+    (Edge::FU, [Up, Front]),
+    (Edge::RU, [Up, Right]),
+    (Edge::BU, [Up, Back]),
+    (Edge::LU, [Up, Left]),
+    (Edge::FD, [Down, Front]),
+    (Edge::RD, [Down, Right]),
+    (Edge::BD, [Down, Back]),
+    (Edge::LD, [Down, Left]),
+    (Edge::FR, [Front, Right]),
+    (Edge::FL, [Front, Left]),
+    (Edge::BR, [Back, Right]),
+    (Edge::BL, [Back, Left]),
+];
+
+fn face_mask(faces: &[Face]) -> u32 {
+    faces.iter().fold(0, |mask, &face| mask | 1 << face as u32)
+}
+
+/// Parses a piece name into its faces, rejecting repeated faces.
+fn parse_faces(name: &str) -> Result<Vec<Face>, &'static str> {
+    let mut faces = Vec::with_capacity(3);
+    for symbol in name.chars() {
+        let face: Face = symbol.encode_utf8(&mut [0; 4]).parse()?;
+        if faces.contains(&face) {
+            return Err("Repeated face in piece name.");
+        }
+        faces.push(face);
+    }
+    Ok(faces)
+}
+
+/// Formats the corner by its Singmaster name, e.g. `UFR`.
+impl fmt::Display for Corner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, faces) = REID_CORNERS.iter().find(|(c, _)| c == self).unwrap();
+        faces.iter().try_for_each(|face| write!(f, "{}", face))
+    }
+}
+
+/// Parses a corner from its Singmaster name, as it is formatted.
+///
+/// Other orders of the same faces are rejected: a rotated name is a twisted
+/// corner, which a [Corner] cannot hold, and a reflected name is no corner
+/// at all.
+///
+/// ```
+/// use cubie::Corner;
+/// assert_eq!("UFR".parse(), Ok(Corner::URF));
+/// assert!("FRU".parse::<Corner>().is_err());
+/// assert!("UFB".parse::<Corner>().is_err());
+/// ```
+impl FromStr for Corner {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let faces = parse_faces(s)?;
+        let mask = face_mask(&faces);
+        let &(corner, corner_faces) = REID_CORNERS
+            .iter()
+            .find(|(_, corner_faces)| faces.len() == 3 && face_mask(corner_faces) == mask)
+            .ok_or("Not a corner piece.")?;
+        if faces != corner_faces {
+            return Err("Corner name not in its canonical order.");
+        }
+        Ok(corner)
+    }
+}
+
+/// Formats the edge by its Singmaster name, e.g. `UF` or `FR`.
+impl fmt::Display for Edge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, faces) = REID_EDGES.iter().find(|(e, _)| e == self).unwrap();
+        faces.iter().try_for_each(|face| write!(f, "{}", face))
+    }
+}
+
+/// Parses an edge from the two faces it touches, in either order, so its
+/// orientation is not kept.
+///
+/// ```
+/// use cubie::Edge;
+/// assert_eq!("UF".parse(), Ok(Edge::FU));
+/// assert_eq!("FU".parse(), Ok(Edge::FU));
+/// assert!("UD".parse::<Edge>().is_err());
+/// ```
+impl FromStr for Edge {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let faces = parse_faces(s)?;
+        let mask = face_mask(&faces);
+        REID_EDGES
+            .iter()
+            .find(|(_, edge_faces)| faces.len() == 2 && face_mask(edge_faces) == mask)
+            .map(|&(edge, _)| edge)
+            .ok_or("Not an edge piece.")
+    }
+}
+
+fn corner_tile(corner: Corner, face: Face) -> usize {
+    *tiles_from_corner(corner)
+        .iter()
+        .find(|&&tile| tile / 9 == face as usize)
+        .unwrap()
+}
+
+fn edge_tile(edge: Edge, face: Face) -> usize {
+    let (a, b) = tiles_from_edge(edge);
+    if a / 9 == face as usize {
+        a
+    } else {
+        b
+    }
+}
+
+fn write_corners(tiles: &TileMap, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, &(corner, faces)) in REID_CORNERS.iter().enumerate() {
+        if i > 0 {
+            f.write_str(" ")?;
+        }
+        for &face in faces.iter() {
+            match tiles.as_array()[corner_tile(corner, face)] {
+                Some(sticker) => write!(f, "{}", sticker)?,
+                None => f.write_str(".")?,
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_edges(tiles: &TileMap, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (i, &(edge, faces)) in REID_EDGES.iter().enumerate() {
+        if i > 0 {
+            f.write_str(" ")?;
+        }
+        for &face in faces.iter() {
+            match tiles.as_array()[edge_tile(edge, face)] {
+                Some(sticker) => write!(f, "{}", sticker)?,
+                None => f.write_str(".")?,
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_token(
+    tiles: &mut TileMap,
+    token: Option<&str>,
+    positions: impl Iterator<Item = usize>,
+) -> Result<(), TileMapConversionError> {
+    let token = token.ok_or(TileMapConversionError::MissingTile)?;
+    let mut symbols = token.chars();
+    for tile in positions {
+        let symbol = symbols.next().ok_or(TileMapConversionError::MissingTile)?;
+        tiles.as_array_mut()[tile] = Some(
+            symbol
+                .encode_utf8(&mut [0; 4])
+                .parse()
+                .map_err(|_| TileMapConversionError::InvalidPiece)?,
+        );
+    }
+    if symbols.next().is_some() {
+        return Err(TileMapConversionError::InvalidPiece);
+    }
+    Ok(())
+}
+
+fn read_corners<'a>(
+    tiles: &mut TileMap,
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> Result<(), TileMapConversionError> {
+    for &(corner, faces) in REID_CORNERS.iter() {
+        let positions = faces.iter().map(|&face| corner_tile(corner, face));
+        read_token(tiles, tokens.next(), positions)?;
+    }
+    Ok(())
+}
+
+fn read_edges<'a>(
+    tiles: &mut TileMap,
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> Result<(), TileMapConversionError> {
+    for &(edge, faces) in REID_EDGES.iter() {
+        let positions = faces.iter().map(|&face| edge_tile(edge, face));
+        read_token(tiles, tokens.next(), positions)?;
+    }
+    Ok(())
+}
+
+/// Formats the state in Reid's notation.
+///
+/// # Example
+/// ```
+/// use cubie::FixedCentersCube;
+/// assert_eq!(
+///     FixedCentersCube::default().to_string(),
+///     "UF UR UB UL DF DR DB DL FR FL BR BL UFR URB UBL ULF DRF DFL DLB DBR"
+/// );
+/// ```
+impl fmt::Display for FixedCentersCube {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tiles = TileMap::from(*self);
+        write_edges(&tiles, f)?;
+        f.write_str(" ")?;
+        write_corners(&tiles, f)
+    }
+}
+
+/// Parses a state in Reid's notation, 12 edges followed by 8 corners
+/// separated by whitespace.
+///
+/// ```
+/// use cubie::{FaceMove::*, FixedCentersCube};
+/// let cube = FixedCentersCube::default() * R1 * U1;
+/// assert_eq!(cube.to_string().parse(), Ok(cube));
+/// ```
+impl FromStr for FixedCentersCube {
+    type Err = TileMapConversionError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tiles = TileMap::default();
+        tiles.store_identity_centers();
+        let mut tokens = s.split_whitespace();
+        read_edges(&mut tiles, &mut tokens)?;
+        read_corners(&mut tiles, &mut tokens)?;
+        if tokens.next().is_some() {
+            return Err(TileMapConversionError::InvalidPiece);
+        }
+        tiles.fc_cube()
+    }
+}
+
+/// Formats the corners in Reid's notation, e.g. `UFR URB UBL ULF DRF DFL DLB DBR`.
+impl fmt::Display for CornerMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_corners(&TileMap::from(*self), f)
+    }
+}
+
+/// Parses the 8 corners of a state in Reid's notation.
+impl FromStr for CornerMap {
+    type Err = TileMapConversionError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tiles = TileMap::default();
+        let mut tokens = s.split_whitespace();
+        read_corners(&mut tiles, &mut tokens)?;
+        if tokens.next().is_some() {
+            return Err(TileMapConversionError::InvalidPiece);
+        }
+        tiles.corners()
+    }
+}
+
+/// Formats the edges in Reid's notation, e.g. `UF UR UB UL DF ...`.
+impl fmt::Display for EdgeMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_edges(&TileMap::from(*self), f)
+    }
+}
+
+/// Parses the 12 edges of a state in Reid's notation.
+impl FromStr for EdgeMap {
+    type Err = TileMapConversionError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tiles = TileMap::default();
+        let mut tokens = s.split_whitespace();
+        read_edges(&mut tiles, &mut tokens)?;
+        if tokens.next().is_some() {
+            return Err(TileMapConversionError::InvalidPiece);
+        }
+        tiles.edges()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FaceMove::*;
+
+    #[test]
+    fn piece_names() {
+        for (corner, _) in REID_CORNERS.iter() {
+            assert_eq!(corner.to_string().parse(), Ok(*corner));
+        }
+        for (edge, _) in REID_EDGES.iter() {
+            assert_eq!(edge.to_string().parse(), Ok(*edge));
+        }
+        assert_eq!(Corner::DRB.to_string(), "DBR");
+        assert_eq!(Edge::BL.to_string(), "BL");
+        assert!("UU".parse::<Edge>().is_err());
+        assert!("UFRB".parse::<Corner>().is_err());
+        // Only the canonical order names a corner, not its twists or
+        // reflections.
+        for name in &["FRU", "RUF", "URF", "RFU", "FUR"] {
+            assert!(name.parse::<Corner>().is_err());
+        }
+    }
+
+    #[test]
+    fn reid_states() {
+        // Known Reid strings for single face turns.
+        let r1 = FixedCentersCube::default() * R1;
+        assert_eq!(
+            r1.to_string(),
+            "UF FR UB UL DF BR DB DL DR FL UR BL FDR FRU UBL ULF BRD DFL DLB BUR"
+        );
+        let u1 = FixedCentersCube::default() * U1;
+        assert_eq!(
+            u1.to_string(),
+            "UR UB UL UF DF DR DB DL FR FL BR BL URB UBL ULF UFR DRF DFL DLB DBR"
+        );
+        let mut rng = oorandom::Rand32::new(9);
+        for _ in 0..100 {
+            let cube = FixedCentersCube::random(&mut rng);
+            assert_eq!(cube.to_string().parse(), Ok(cube));
+            assert_eq!(cube.corners().to_string().parse(), Ok(cube.corners()));
+            assert_eq!(cube.edges().to_string().parse(), Ok(cube.edges()));
+        }
+    }
+
+    #[test]
+    fn invalid_reid_states() {
+        use TileMapConversionError::*;
+        let parse = |s: &str| s.parse::<FixedCentersCube>();
+        assert_eq!(parse("UF UR"), Err(MissingTile));
+        assert_eq!(
+            parse("UF UR UB UL DF DR DB DL FR FL BR BL UFR URB UBL ULF DRF DFL DLB DBR UF"),
+            Err(InvalidPiece)
+        );
+        // A reflected corner name is not a physical orientation.
+        assert!(
+            parse("UF UR UB UL DF DR DB DL FR FL BR BL URF URB UBL ULF DRF DFL DLB DBR").is_err()
+        );
+        // Two identical edges.
+        assert!(
+            parse("UF UF UB UL DF DR DB DL FR FL BR BL UFR URB UBL ULF DRF DFL DLB DBR").is_err()
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Optimal solutions to F2L pairs and XCrosses.
+//!
+//! A pair is a corner of the cross layer with the edge above it, and it is
+//! solved when both pieces are in their slot. [F2lSolver] finds every
+//! shortest solution that inserts a pair while keeping the cross and the
+//! pairs already solved, or that solves an XCross, the cross together with
+//! one pair, from any state.
+//!
+//! ```
+//! use cubie::f2l::F2lSolver;
+//! use cubie::{Corner, Cube, Face, Move::*};
+//!
+//! let solver = F2lSolver::new();
+//! let cube = Cube::from(&[R1, U1, R3, U3]);
+//! let solutions = solver.solve_pair(cube, Face::Down, Corner::DRF).unwrap();
+//! assert!(solutions.contains(&vec![U1, R1, U3, R3]));
+//! ```
+use crate::cross::{down_symmetry, CrossSolver};
+use crate::{Corner, Cube, Edge, Face, FaceMove, FixedCentersCube, Move};
+use std::fmt;
+
+/// The corners of the slots around the down cross.
+const SLOTS: [Corner; 4] = [Corner::DRF, Corner::DLF, Corner::DRB, Corner::DLB];
+
+/// The number of positions of a corner and an edge: 8 * 3 * 12 * 2.
+const PAIR_POSITIONS: usize = 576;
+
+fn corner_faces(corner: Corner) -> [Face; 3] {
+    [corner.x(), corner.y(), corner.z()]
+}
+
+fn corner_with_faces(faces: [Face; 3]) -> Corner {
+    Corner::corners()
+        .find(|&corner| faces.iter().all(|face| corner_faces(corner).contains(face)))
+        .unwrap()
+}
+
+/// The edge next to the corner of a slot, away from the cross.
+fn slot_edge(slot: Corner, cross: Face) -> Edge {
+    let faces = corner_faces(slot);
+    let sides: Vec<Face> = faces.iter().copied().filter(|&f| f != cross).collect();
+    Edge::edges()
+        .find(|edge| {
+            let (a, b) = edge.faces();
+            sides.contains(&a) && sides.contains(&b)
+        })
+        .unwrap()
+}
+
+fn pair_index(cube: FixedCentersCube, slot: Corner) -> usize {
+    let (corner, twist) = cube.corners().get(slot);
+    let (edge, flip) = cube.edges().get(slot_edge(slot, Face::Down));
+    ((corner as usize * 3 + twist as usize) * 12 + edge as usize) * 2 + flip as usize
+}
+
+/// The reason an F2L search failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum F2lError {
+    /// The slot is not next to the cross.
+    InvalidSlot,
+    /// The cross must be solved before inserting a pair.
+    UnsolvedCross,
+    /// There is no solution within the maximum length using the allowed
+    /// faces.
+    NotFound,
+}
+
+impl fmt::Display for F2lError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            F2lError::InvalidSlot => "slot is not adjacent to the cross",
+            F2lError::UnsolvedCross => "cross is not solved",
+            F2lError::NotFound => "no solution within the maximum length",
+        })
+    }
+}
+
+impl std::error::Error for F2lError {}
+
+/// A solver for F2L pairs and XCrosses, returning all optimal solutions.
+///
+/// The search is an IDA* bounded by the distance to the cross and to each
+/// pair that must end up solved. Slots are named by the corner of their
+/// colors, so [Corner::DRF] is the slot between the down, right and front
+/// centers.
+pub struct F2lSolver {
+    cross: CrossSolver,
+    /// The distance table of the pair of each slot in [SLOTS].
+    pairs: [Vec<u8>; 4],
+    faces: Vec<Face>,
+    max_length: u8,
+}
+
+impl Default for F2lSolver {
+    fn default() -> F2lSolver {
+        F2lSolver::new()
+    }
+}
+
+impl F2lSolver {
+    /// Builds the solver and its distance tables, allowing turns of every
+    /// face and solutions of up to 14 moves.
+    pub fn new() -> F2lSolver {
+        let pair_table = |slot| {
+            let mut distances = vec![u8::MAX; PAIR_POSITIONS];
+            distances[pair_index(FixedCentersCube::default(), slot)] = 0;
+            let mut frontier = vec![FixedCentersCube::default()];
+            let mut depth = 0;
+            while !frontier.is_empty() {
+                depth += 1;
+                let mut next = Vec::new();
+                for &cube in &frontier {
+                    for mv in FaceMove::moves() {
+                        let moved = cube * mv;
+                        let distance = &mut distances[pair_index(moved, slot)];
+                        if *distance == u8::MAX {
+                            *distance = depth;
+                            next.push(moved);
+                        }
+                    }
+                }
+                frontier = next;
+            }
+            distances
+        };
+        F2lSolver {
+            cross: CrossSolver::new(),
+            pairs: SLOTS.map(pair_table),
+            faces: Face::faces().collect(),
+            max_length: 14,
+        }
+    }
+
+    /// Restricts the solutions to turns of the given faces, in the
+    /// orientation the cube is held in. For example `[Right, Up, Left,
+    /// Front]` gives solutions in `<R, U, L, F>`.
+    pub fn set_faces(&mut self, faces: &[Face]) {
+        self.faces = faces.to_vec();
+    }
+
+    /// Sets the length beyond which the search gives up with
+    /// [F2lError::NotFound].
+    pub fn set_max_length(&mut self, max_length: u8) {
+        self.max_length = max_length;
+    }
+
+    /// Finds all the shortest solutions inserting the pair of `slot`, on a
+    /// cube with the cross of color `cross` solved. Pairs that are already
+    /// solved stay solved.
+    ///
+    /// The solutions are made of face turns in the orientation the cube is
+    /// held in, without consecutive moves of the same face and with moves of
+    /// opposite faces in a single order.
+    pub fn solve_pair(
+        &self,
+        cube: Cube,
+        cross: Face,
+        slot: Corner,
+    ) -> Result<Vec<Vec<Move>>, F2lError> {
+        self.solve(cube, cross, slot, false)
+    }
+
+    /// Finds all the shortest solutions of the XCross made of the cross of
+    /// color `cross` and the pair of `slot`, from any state.
+    pub fn solve_xcross(
+        &self,
+        cube: Cube,
+        cross: Face,
+        slot: Corner,
+    ) -> Result<Vec<Vec<Move>>, F2lError> {
+        self.solve(cube, cross, slot, true)
+    }
+
+    fn solve(
+        &self,
+        cube: Cube,
+        cross: Face,
+        slot: Corner,
+        xcross: bool,
+    ) -> Result<Vec<Vec<Move>>, F2lError> {
+        if !corner_faces(slot).contains(&cross) {
+            return Err(F2lError::InvalidSlot);
+        }
+        let sym = down_symmetry(cross);
+        let state = FixedCentersCube::from(cube).conjugate(sym);
+        if !xcross && self.cross.down_distance(state.edges()) != 0 {
+            return Err(F2lError::UnsolvedCross);
+        }
+        let slot = corner_with_faces(corner_faces(slot).map(|face| sym.face(face)));
+        let kept: Vec<usize> = (0..4)
+            .filter(|&i| {
+                SLOTS[i] == slot || (!xcross && self.pairs[i][pair_index(state, SLOTS[i])] == 0)
+            })
+            .collect();
+
+        let inverse = sym.inverse();
+        let rotation = cube.centers();
+        let held = |mv: FaceMove| Move::from(mv).conjugate(inverse).projection(rotation);
+        let moves: Vec<FaceMove> = FaceMove::moves()
+            .filter(|&mv| self.faces.contains(&held(mv).face()))
+            .collect();
+
+        let search = Search {
+            solver: self,
+            kept: &kept,
+            moves: &moves,
+        };
+        for depth in search.bound(state)..=self.max_length {
+            let mut solutions = Vec::new();
+            search.search(state, depth, None, &mut Vec::new(), &mut solutions);
+            if !solutions.is_empty() {
+                return Ok(solutions
+                    .into_iter()
+                    .map(|solution| solution.into_iter().map(held).collect())
+                    .collect());
+            }
+        }
+        Err(F2lError::NotFound)
+    }
+}
+
+struct Search<'a> {
+    solver: &'a F2lSolver,
+    /// The slots that must be solved, indices into [SLOTS].
+    kept: &'a [usize],
+    moves: &'a [FaceMove],
+}
+
+impl Search<'_> {
+    /// A lower bound on the number of moves to the goal, which is zero only
+    /// at the goal.
+    fn bound(&self, state: FixedCentersCube) -> u8 {
+        let cross = self.solver.cross.down_distance(state.edges());
+        self.kept
+            .iter()
+            .map(|&i| self.solver.pairs[i][pair_index(state, SLOTS[i])])
+            .fold(cross, u8::max)
+    }
+
+    fn search(
+        &self,
+        state: FixedCentersCube,
+        depth: u8,
+        last: Option<Face>,
+        path: &mut Vec<FaceMove>,
+        solutions: &mut Vec<Vec<FaceMove>>,
+    ) {
+        if depth == 0 {
+            solutions.push(path.clone());
+            return;
+        }
+        for &mv in self.moves {
+            let face = mv.face();
+            if let Some(last) = last {
+                // Moves of opposite faces commute, keep only one order.
+                if face == last || (face == last.opposite() && (face as u8) < last as u8) {
+                    continue;
+                }
+            }
+            let moved = state * mv;
+            if self.bound(moved) >= depth {
+                continue;
+            }
+            path.push(mv);
+            self.search(moved, depth - 1, Some(face), path, solutions);
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Move::*;
+    use crate::{Algorithm, CornerOrientation, EdgeOrientation};
+
+    fn pair_solved(cube: Cube, cross: Face, slot: Corner) -> bool {
+        let cube = FixedCentersCube::from(cube);
+        let edge = slot_edge(slot, cross);
+        cube.corners().get(slot) == (slot, CornerOrientation::Identity)
+            && cube.edges().get(edge) == (edge, EdgeOrientation::Identity)
+    }
+
+    fn cross_solved(cube: Cube, cross: Face) -> bool {
+        let edges = FixedCentersCube::from(cube).edges();
+        Edge::edges()
+            .filter(|edge| edge.faces().0 == cross || edge.faces().1 == cross)
+            .all(|edge| edges.get(edge) == (edge, EdgeOrientation::Identity))
+    }
+
+    #[test]
+    fn pair_keeps_solved_pairs() {
+        let mut solver = F2lSolver::new();
+        let scramble: Algorithm = "R U R' U2 y L' U' L y'".parse().unwrap();
+        let cube = scramble.cube();
+        assert_eq!(
+            solver.solve_pair(cube, Face::Down, Corner::ULB),
+            Err(F2lError::InvalidSlot)
+        );
+        assert_eq!(
+            solver.solve_pair(cube, Face::Up, Corner::ULB),
+            Err(F2lError::UnsolvedCross)
+        );
+        let kept: Vec<Corner> = SLOTS
+            .iter()
+            .copied()
+            .filter(|&slot| pair_solved(cube, Face::Down, slot))
+            .collect();
+        for &slot in &SLOTS {
+            let solutions = solver.solve_pair(cube, Face::Down, slot).unwrap();
+            for solution in &solutions {
+                assert_eq!(solution.len(), solutions[0].len());
+                let solved = cube * Cube::from(solution);
+                assert!(cross_solved(solved, Face::Down));
+                assert!(pair_solved(solved, Face::Down, slot));
+                assert!(kept
+                    .iter()
+                    .all(|&kept| pair_solved(solved, Face::Down, kept)));
+            }
+        }
+
+        solver.set_faces(&[Face::Right, Face::Up]);
+        solver.set_max_length(6);
+        let solutions = solver.solve_pair(Cube::from(&[R1, U1, R3]), Face::Down, Corner::DRF);
+        assert_eq!(solutions, Ok(vec![vec![R1, U3, R3]]));
+        assert_eq!(
+            solver.solve_pair(Cube::from(&[L3, U3, L1]), Face::Down, Corner::DLF),
+            Err(F2lError::NotFound)
+        );
+    }
+
+    #[test]
+    fn xcross() {
+        let solver = F2lSolver::new();
+        let mut rng = oorandom::Rand32::new(18);
+        for &(cross, slot) in &[(Face::Down, Corner::DLB), (Face::Front, Corner::ULF)] {
+            let cube = Cube::random(&mut rng) * Move::Y1;
+            let solutions = solver.solve_xcross(cube, cross, slot).unwrap();
+            let length = solutions[0].len();
+            assert!(length >= solver.cross.distance(cube, cross) as usize);
+            for solution in &solutions {
+                assert_eq!(solution.len(), length);
+                let solved = cube * Cube::from(solution);
+                assert!(cross_solved(solved, cross));
+                assert!(pair_solved(solved, cross, slot));
+            }
+        }
+    }
+}
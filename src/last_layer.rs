@@ -0,0 +1,850 @@
+//! Recognition of last layer cases, with a default algorithm for each.
+//!
+//! The last layer is the layer on top of the cube as it is held: the cube
+//! may be in any orientation, and its first two layers must be solved
+//! relative to the centers. Cases are recognized from the stickers, and the
+//! adjustments of the upper face (AUF) needed before and after the default
+//! algorithm are returned with the case.
+//!
+//! Every stored algorithm is checked against the cube algebra by the tests,
+//! and every state of the last layer is recognized.
+//!
+//! ```
+//! use cubie::last_layer::{recognize_oll, recognize_pll, Oll, Pll};
+//! use cubie::{Algorithm, Cube, Move::*};
+//!
+//! let sune: Algorithm = "R U R' U R U2 R'".parse().unwrap();
+//! let mut cube = Cube::default() * U1 * sune.inverse();
+//! let oll = recognize_oll(cube).unwrap();
+//! assert_eq!(oll.case, Oll::new(27));
+//! cube = cube * oll.algorithm();
+//!
+//! let pll = recognize_pll(cube).unwrap();
+//! cube = cube * pll.algorithm();
+//! assert!(cube.is_solved());
+//! ```
+use crate::{Algorithm, Cube, Face, Move, TileMap};
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Default algorithms of the OLL cases, indexed by case number minus one.
+const OLL_ALGORITHMS: [&str; 57] = [
+    "R U2 R2 F R F' U2 R' F R F'",
+    "F R U R' U' F' f R U R' U' f'",
+    "f R U R' U' f' U' F R U R' U' F'",
+    "f R U R' U' f' U F R U R' U' F'",
+    "r' U2 R U R' U r",
+    "r U2 R' U' R U' r'",
+    "r U R' U R U2 r'",
+    "l' U' L U' L' U2 l",
+    "R U R' U' R' F R2 U R' U' F'",
+    "R U R' U R' F R F' R U2 R'",
+    "r U R' U R' F R F' R U2 r'",
+    "M' R' U' R U' R' U2 R U' M",
+    "F U R U' R2 F' R U R U' R'",
+    "R' F R U R' F' R F U' F'",
+    "l' U' l L' U' L U l' U l",
+    "r U r' R U R' U' r U' r'",
+    "R U R' U R' F R F' U2 R' F R F'",
+    "r U R' U R U2 r2 U' R U' R' U2 r",
+    "r' R U R U R' U' M' R' F R F'",
+    "r U R' U' M2 U R U' R' U' M'",
+    "R U2 R' U' R U R' U' R U' R'",
+    "R U2 R2 U' R2 U' R2 U2 R",
+    "R2 D' R U2 R' D R U2 R",
+    "r U R' U' r' F R F'",
+    "F' r U R' U' r' F R",
+    "R U2 R' U' R U' R'",
+    "R U R' U R U2 R'",
+    "r U R' U' r' R U R U' R'",
+    "R U R' U' R U' R' F' U' F R U R'",
+    "F R' F R2 U' R' U' R U R' F2",
+    "R' U' F U R U' R' F' R",
+    "L U F' U' L' U L F L'",
+    "R U R' U' R' F R F'",
+    "R U R2 U' R' F R U R U' F'",
+    "R U2 R2 F R F' R U2 R'",
+    "L' U' L U' L' U L U L F' L' F",
+    "F R' F' R U R U' R'",
+    "R U R' U R U' R' U' R' F R F'",
+    "L F' L' U' L U F U' L'",
+    "R' F R U R' U' F' U R",
+    "R U R' U R U2 R' F R U R' U' F'",
+    "R' U' R U' R' U2 R F R U R' U' F'",
+    "F' U' L' U L F",
+    "F U R U' R' F'",
+    "F R U R' U' F'",
+    "R' U' R' F R F' U R",
+    "R' U' R' F R F' R' F R F' U R",
+    "F R U R' U' R U R' U' F'",
+    "r U' r2 U r2 U r2 U' r",
+    "r' U r2 U' r2 U' r2 U r'",
+    "F U R U' R' U R U' R' F'",
+    "R U R' U R U' B U' B' R'",
+    "l' U2 L U L' U' L U L' U l",
+    "r U2 R' U' R U R' U' R U' r'",
+    "R' F R U R U' R2 F' R2 U' R' U R U R'",
+    "r' U' r U' R' U R U' R' U R r' U r",
+    "R U R' U' M' U R U' r'",
+];
+
+/// One of the 57 cases of orienting the last layer, numbered as usual.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Oll(u8);
+
+impl Oll {
+    /// The case with the given number, if it is from 1 to 57.
+    pub fn new(number: u8) -> Option<Oll> {
+        if (1..=57).contains(&number) {
+            Some(Oll(number))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn number(self) -> u8 {
+        self.0
+    }
+
+    /// An iterator over all 57 cases.
+    pub fn cases() -> impl Iterator<Item = Oll> {
+        (1..=57).map(Oll)
+    }
+
+    /// The default algorithm orienting the last layer from this case.
+    pub fn algorithm(self) -> Algorithm {
+        OLL_ALGORITHMS[self.0 as usize - 1].parse().unwrap()
+    }
+}
+
+impl fmt::Display for Oll {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OLL {}", self.0)
+    }
+}
+
+/// One of the 21 cases of permuting an oriented last layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Pll {
+    Aa,
+    Ab,
+    E,
+    F,
+    Ga,
+    Gb,
+    Gc,
+    Gd,
+    H,
+    Ja,
+    Jb,
+    Na,
+    Nb,
+    Ra,
+    Rb,
+    T,
+    Ua,
+    Ub,
+    V,
+    Y,
+    Z,
+}
+
+impl Pll {
+    /// All 21 cases.
+    pub const ALL: [Pll; 21] = [
+        Pll::Aa,
+        Pll::Ab,
+        Pll::E,
+        Pll::F,
+        Pll::Ga,
+        Pll::Gb,
+        Pll::Gc,
+        Pll::Gd,
+        Pll::H,
+        Pll::Ja,
+        Pll::Jb,
+        Pll::Na,
+        Pll::Nb,
+        Pll::Ra,
+        Pll::Rb,
+        Pll::T,
+        Pll::Ua,
+        Pll::Ub,
+        Pll::V,
+        Pll::Y,
+        Pll::Z,
+    ];
+
+    /// The default algorithm solving the last layer from this case.
+    pub fn algorithm(self) -> Algorithm {
+        let alg = match self {
+            Pll::Aa => "x R' U R' D2 R U' R' D2 R2 x'",
+            Pll::Ab => "x R2 D2 R U R' D2 R U' R x'",
+            Pll::E => "x' R U' R' D R U R' D' R U R' D R U' R' D' x",
+            Pll::F => "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R",
+            Pll::Ga => "R2 U R' U R' U' R U' R2 U' D R' U R D'",
+            Pll::Gb => "R' U' R U D' R2 U R' U R U' R U' R2 D",
+            Pll::Gc => "R2 U' R U' R U R' U R2 U D' R U' R' D",
+            Pll::Gd => "R U R' U' D R2 U' R U' R' U R' U R2 D'",
+            Pll::H => "M2 U M2 U2 M2 U M2",
+            Pll::Ja => "R' U L' U2 R U' R' U2 R L",
+            Pll::Jb => "R U R' F' R U R' U' R' F R2 U' R'",
+            Pll::Na => "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+            Pll::Nb => "R' U R U' R' F' U' F R U R' F R' F' R U' R",
+            Pll::Ra => "R U' R' U' R U R D R' U' R D' R' U2 R'",
+            Pll::Rb => "R2 F R U R U' R' F' R U2 R' U2 R",
+            Pll::T => "R U R' U' R' F R2 U' R' U' R U R' F'",
+            Pll::Ua => "R U' R U R U R U' R' U' R2",
+            Pll::Ub => "R2 U R U R' U' R' U' R' U R'",
+            Pll::V => "R' U R' U' B' R' B2 U' B' U B' R B R",
+            Pll::Y => "F R U' R' U' R U R' F' R U R' U' R' F R F'",
+            Pll::Z => "M' U M2 U M2 U M' U2 M2",
+        };
+        alg.parse().unwrap()
+    }
+}
+
+impl fmt::Display for Pll {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Default algorithms of the COLL cases, indexed by case number minus one,
+/// each with the OLL case of its corners, or 0 if they are oriented.
+const COLL_ALGORITHMS: [(u8, &str); 42] = [
+    (0, "R' U L' U2 R U' R' U2 R L"),
+    (0, "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    (21, "L' U' L U' L' U' R U' L U R'"),
+    (21, "R U R' U R U' R' U R U2 R'"),
+    (21, "R' F2 R2 U2 R' F2 R U2 R2 F2 R"),
+    (21, "F R U' R' U R U2 R' U' R U R' U' F'"),
+    (22, "R U2 R2 U' R2 U' R2 U2 R"),
+    (22, "R U' L' U R' U L U L' U L"),
+    (22, "R U R' U F2 R U2 R' U2 R' F2 R"),
+    (22, "R' F2 R U2 R U2 R' F2 U' R U' R'"),
+    (22, "F U R U' R' U R U2 R' U' R U R' F'"),
+    (22, "R2 D R' U2 R D' R' U2 R' U' L' U R U' L U R'"),
+    (23, "L2 D' L U2 L' D L U2 L"),
+    (23, "R2 D R' U2 R D' R' U2 R'"),
+    (23, "F R U' R' U R U R' U R U' R' F'"),
+    (23, "R' U' R F R2 D' R U R' D R2 U' F'"),
+    (23, "R' U' R U' R' U2 R2 U R' U R U2 R'"),
+    (23, "R U' L' U R' U' L U2 L' U R U' L U R'"),
+    (24, "l' U' L U l F' L' F"),
+    (24, "r U R' U' r' F R F'"),
+    (24, "R' U R U2 L' R' U R U' L"),
+    (24, "R' U R2 D r' U2 r D' R2 U' R"),
+    (24, "F R U R' U' R U' R' U' R U R' F'"),
+    (24, "R U2 R' U' R U' R2 U2 R U R' U R"),
+    (25, "F R' F' r U R U' r'"),
+    (25, "F' L F l' U' L' U l"),
+    (25, "L' U2 L' D' L U2 L' D L2"),
+    (25, "R U2 R D R' U2 R D' R2"),
+    (25, "F R U R' U' R U' R' U2 R U2 R' U' F'"),
+    (25, "R U R' U R U2 R' L U2 L' U' L U' L'"),
+    (26, "L' U R U' L U R'"),
+    (26, "R U2 R' U' R U' R'"),
+    (26, "R L' U' L U R' U2 L' U2 L"),
+    (26, "R U2 R' U2 L' U R U' R' L"),
+    (26, "R2 D R' U R D' R' U R' U' R U' R'"),
+    (26, "l' U' L U l F' L' F L' U R U' L U R'"),
+    (27, "R U R' U R U2 R'"),
+    (27, "R U' L' U R' U' L"),
+    (27, "L' R U R' U' L U2 R U2 R'"),
+    (27, "L' U2 L U2 R U' L' U L R'"),
+    (27, "R U R' U R U' R D R' U' R D' R2"),
+    (27, "r U R' U' r' F R F' R U' L' U R' U' L"),
+];
+
+/// One of the 42 cases of solving the corners of a last layer whose edges
+/// are oriented, keeping them oriented.
+///
+/// Cases are grouped by the shape of their corners, which is one of the
+/// OLL cases with a cross, and numbered within each shape in this crate's
+/// order, since sources disagree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Coll(u8);
+
+impl Coll {
+    /// The case with the given number, if it is from 1 to 42.
+    pub fn new(number: u8) -> Option<Coll> {
+        if (1..=42).contains(&number) {
+            Some(Coll(number))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn number(self) -> u8 {
+        self.0
+    }
+
+    /// An iterator over all 42 cases.
+    pub fn cases() -> impl Iterator<Item = Coll> {
+        (1..=42).map(Coll)
+    }
+
+    /// The OLL case of the corners, or `None` if they are oriented.
+    pub fn oll(self) -> Option<Oll> {
+        Oll::new(COLL_ALGORITHMS[self.0 as usize - 1].0)
+    }
+
+    /// The default algorithm solving the corners from this case.
+    pub fn algorithm(self) -> Algorithm {
+        COLL_ALGORITHMS[self.0 as usize - 1].1.parse().unwrap()
+    }
+}
+
+/// Formats the case by its shape and its number within the shape, as in
+/// `COLL Pi3`.
+impl fmt::Display for Coll {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shape = match self.oll().map(Oll::number) {
+            None => "O",
+            Some(21) => "H",
+            Some(22) => "Pi",
+            Some(23) => "U",
+            Some(24) => "T",
+            Some(25) => "L",
+            Some(26) => "AS",
+            Some(_) => "S",
+        };
+        let index = Coll::cases()
+            .take_while(|&case| case != *self)
+            .filter(|case| case.oll() == self.oll())
+            .count();
+        write!(f, "COLL {}{}", shape, index + 1)
+    }
+}
+
+/// The reason a cube is not in a last layer case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecognitionError {
+    /// The first two layers are not solved.
+    UnsolvedF2L,
+    /// The last layer is not oriented, as required for PLL.
+    UnorientedLastLayer,
+    /// The edges of the last layer are not oriented, as required for COLL.
+    UnorientedEdges,
+    /// The state cannot be solved, so it is in no case.
+    Unsolvable,
+}
+
+impl fmt::Display for RecognitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RecognitionError::UnsolvedF2L => "first two layers are not solved",
+            RecognitionError::UnorientedLastLayer => "last layer is not oriented",
+            RecognitionError::UnorientedEdges => "last layer edges are not oriented",
+            RecognitionError::Unsolvable => "state is unsolvable",
+        })
+    }
+}
+
+impl std::error::Error for RecognitionError {}
+
+/// A recognized OLL case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OllMatch {
+    /// The case, or `None` if the last layer is already oriented.
+    pub case: Option<Oll>,
+    /// The turn of the upper face to make before the case's algorithm.
+    pub pre_auf: Option<Move>,
+}
+
+impl OllMatch {
+    /// The moves orienting the last layer: the pre-AUF and the algorithm.
+    pub fn algorithm(&self) -> Algorithm {
+        let mut alg: Algorithm = self.pre_auf.into_iter().collect();
+        if let Some(case) = self.case {
+            alg *= &case.algorithm();
+        }
+        alg
+    }
+}
+
+/// A recognized PLL case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PllMatch {
+    /// The case, or `None` if the last layer is solved up to a turn of the
+    /// upper face, given as the post-AUF.
+    pub case: Option<Pll>,
+    /// The turn of the upper face to make before the case's algorithm.
+    pub pre_auf: Option<Move>,
+    /// The turn of the upper face to make after the case's algorithm.
+    pub post_auf: Option<Move>,
+}
+
+impl PllMatch {
+    /// The moves solving the cube: the pre-AUF, the algorithm and the
+    /// post-AUF.
+    pub fn algorithm(&self) -> Algorithm {
+        let mut alg: Algorithm = self.pre_auf.into_iter().collect();
+        if let Some(case) = self.case {
+            alg *= &case.algorithm();
+        }
+        alg.extend(self.post_auf);
+        alg
+    }
+}
+
+/// A recognized COLL case.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CollMatch {
+    /// The case, or `None` if the corners are solved up to a turn of the
+    /// upper face, given as the post-AUF.
+    pub case: Option<Coll>,
+    /// The turn of the upper face to make before the case's algorithm.
+    pub pre_auf: Option<Move>,
+    /// The turn of the upper face to make after the case's algorithm.
+    pub post_auf: Option<Move>,
+}
+
+impl CollMatch {
+    /// The moves solving the corners and keeping the edges oriented: the
+    /// pre-AUF, the algorithm and the post-AUF.
+    pub fn algorithm(&self) -> Algorithm {
+        let mut alg: Algorithm = self.pre_auf.into_iter().collect();
+        if let Some(case) = self.case {
+            alg *= &case.algorithm();
+        }
+        alg.extend(self.post_auf);
+        alg
+    }
+}
+
+const AUFS: [Option<Move>; 4] = [None, Some(Move::U1), Some(Move::U2), Some(Move::U3)];
+
+/// The stickers of the cube, each named after the face its color belongs
+/// to in the current orientation of the cube.
+fn relative_tiles(cube: Cube) -> [Option<Face>; 54] {
+    let tiles = *TileMap::from(cube).as_array();
+    let mut faces = [Face::Up; 6];
+    for face in Face::faces() {
+        if let Some(color) = tiles[face as usize * 9 + 4] {
+            faces[color as usize] = face;
+        }
+    }
+    let mut relative = [None; 54];
+    for (relative, tile) in relative.iter_mut().zip(tiles.iter()) {
+        *relative = tile.map(|color| faces[color as usize]);
+    }
+    relative
+}
+
+/// The stickers of a solvable cube with its first two layers solved.
+fn last_layer_tiles(cube: Cube) -> Result<[Option<Face>; 54], RecognitionError> {
+    if !cube.has_solution() {
+        return Err(RecognitionError::Unsolvable);
+    }
+    let tiles = relative_tiles(cube);
+    if !is_f2l_solved(&tiles) {
+        return Err(RecognitionError::UnsolvedF2L);
+    }
+    Ok(tiles)
+}
+
+fn is_f2l_solved(tiles: &[Option<Face>; 54]) -> bool {
+    let down = Face::Down as usize * 9;
+    let sides = [Face::Front, Face::Back, Face::Right, Face::Left];
+    tiles[down..down + 9].iter().all(|&t| t == Some(Face::Down))
+        && sides.iter().all(|&face| {
+            let start = face as usize * 9;
+            tiles[start + 3..start + 9].iter().all(|&t| t == Some(face))
+        })
+}
+
+/// The positions of the stickers of the upper face color.
+fn orientation_mask(tiles: &[Option<Face>; 54]) -> u64 {
+    tiles
+        .iter()
+        .enumerate()
+        .filter(|&(_, &tile)| tile == Some(Face::Up))
+        .fold(0, |mask, (i, _)| mask | 1 << i)
+}
+
+fn auf_cube(auf: Option<Move>) -> Cube {
+    auf.map_or(Cube::default(), Move::cube)
+}
+
+/// The stickers without those of the edges of the last layer.
+fn corner_tiles(tiles: &[Option<Face>; 54]) -> [Option<Face>; 54] {
+    let mut corners = *tiles;
+    for &tile in &[1, 3, 5, 7, 19, 28, 37, 46] {
+        corners[tile] = None;
+    }
+    corners
+}
+
+/// A state some AUFs and a case's algorithm solve.
+struct CaseState<T> {
+    tiles: [Option<Face>; 54],
+    case: T,
+    pre_auf: Option<Move>,
+    post_auf: Option<Move>,
+}
+
+/// The states solved by each case with every pre-AUF and post-AUF.
+fn case_states<T: Copy>(cases: impl Iterator<Item = (T, Algorithm)>) -> Vec<CaseState<T>> {
+    let mut states = Vec::new();
+    for (case, alg) in cases {
+        let alg = alg.cube();
+        for &pre_auf in &AUFS {
+            for &post_auf in &AUFS {
+                let state = (auf_cube(pre_auf) * alg * auf_cube(post_auf)).inverse();
+                states.push(CaseState {
+                    tiles: relative_tiles(state),
+                    case,
+                    pre_auf,
+                    post_auf,
+                });
+            }
+        }
+    }
+    states
+}
+
+/// The orientation mask of each OLL case, built on first use.
+fn oll_masks() -> &'static [u64; 57] {
+    static MASKS: OnceLock<[u64; 57]> = OnceLock::new();
+    MASKS.get_or_init(|| {
+        let mut masks = [0; 57];
+        for (mask, case) in masks.iter_mut().zip(Oll::cases()) {
+            *mask = orientation_mask(&relative_tiles(case.algorithm().cube().inverse()));
+        }
+        masks
+    })
+}
+
+fn pll_states() -> &'static [CaseState<Pll>] {
+    static STATES: OnceLock<Vec<CaseState<Pll>>> = OnceLock::new();
+    STATES.get_or_init(|| case_states(Pll::ALL.iter().map(|&case| (case, case.algorithm()))))
+}
+
+fn coll_states() -> &'static [CaseState<Coll>] {
+    static STATES: OnceLock<Vec<CaseState<Coll>>> = OnceLock::new();
+    STATES.get_or_init(|| {
+        let mut states = case_states(Coll::cases().map(|case| (case, case.algorithm())));
+        for state in &mut states {
+            state.tiles = corner_tiles(&state.tiles);
+        }
+        states
+    })
+}
+
+/// Identifies the OLL case of a cube with its first two layers solved.
+///
+/// Returns the first matching pre-AUF when several apply, as they do for
+/// symmetric cases.
+pub fn recognize_oll(cube: Cube) -> Result<OllMatch, RecognitionError> {
+    let tiles = last_layer_tiles(cube)?;
+    let solved = orientation_mask(&relative_tiles(Cube::default()));
+    if orientation_mask(&tiles) == solved {
+        return Ok(OllMatch {
+            case: None,
+            pre_auf: None,
+        });
+    }
+    for &pre_auf in &AUFS {
+        let mask = orientation_mask(&relative_tiles(cube * auf_cube(pre_auf)));
+        if let Some(index) = oll_masks().iter().position(|&case| case == mask) {
+            return Ok(OllMatch {
+                case: Some(Oll(index as u8 + 1)),
+                pre_auf,
+            });
+        }
+    }
+    Err(RecognitionError::Unsolvable)
+}
+
+/// Identifies the PLL case of a cube with its first two layers solved and
+/// its last layer oriented.
+///
+/// Returns the first matching pre-AUF and post-AUF when several apply, as
+/// they do for symmetric cases.
+pub fn recognize_pll(cube: Cube) -> Result<PllMatch, RecognitionError> {
+    let tiles = last_layer_tiles(cube)?;
+    let up = Face::Up as usize * 9;
+    if tiles[up..up + 9].iter().any(|&t| t != Some(Face::Up)) {
+        return Err(RecognitionError::UnorientedLastLayer);
+    }
+    for &post_auf in &AUFS {
+        if tiles == relative_tiles(auf_cube(post_auf).inverse()) {
+            return Ok(PllMatch {
+                case: None,
+                pre_auf: None,
+                post_auf,
+            });
+        }
+    }
+    let state = pll_states()
+        .iter()
+        .find(|state| state.tiles == tiles)
+        .ok_or(RecognitionError::Unsolvable)?;
+    Ok(PllMatch {
+        case: Some(state.case),
+        pre_auf: state.pre_auf,
+        post_auf: state.post_auf,
+    })
+}
+
+/// Identifies the COLL case of a cube with its first two layers solved and
+/// the edges of its last layer oriented.
+///
+/// Returns the first matching pre-AUF and post-AUF when several apply, as
+/// they do for symmetric cases. The edges are left oriented, so that only
+/// an edge permutation remains, see [recognize_pll].
+///
+/// # Example
+/// ```
+/// use cubie::last_layer::{recognize_coll, recognize_pll, Pll};
+/// use cubie::{Algorithm, Cube};
+///
+/// let alg: Algorithm = "R U2 R' U' R U' R'".parse().unwrap();
+/// let cube = Cube::default() * alg;
+/// let coll = recognize_coll(cube).unwrap();
+/// let cube = cube * coll.algorithm();
+/// let pll = recognize_pll(cube).unwrap();
+/// assert!(matches!(pll.case, None | Some(Pll::Ua | Pll::Ub | Pll::H | Pll::Z)));
+/// ```
+pub fn recognize_coll(cube: Cube) -> Result<CollMatch, RecognitionError> {
+    let tiles = last_layer_tiles(cube)?;
+    if [1, 3, 5, 7].iter().any(|&i| tiles[i] != Some(Face::Up)) {
+        return Err(RecognitionError::UnorientedEdges);
+    }
+    let corners = corner_tiles(&tiles);
+    for &post_auf in &AUFS {
+        if corners == corner_tiles(&relative_tiles(auf_cube(post_auf).inverse())) {
+            return Ok(CollMatch {
+                case: None,
+                pre_auf: None,
+                post_auf,
+            });
+        }
+    }
+    let state = coll_states()
+        .iter()
+        .find(|state| state.tiles == corners)
+        .ok_or(RecognitionError::Unsolvable)?;
+    Ok(CollMatch {
+        case: Some(state.case),
+        pre_auf: state.pre_auf,
+        post_auf: state.post_auf,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::corner::CornerOrientation;
+    use crate::{CenterMap, Corner, CornerMap, EdgeMap, FixedCentersCube};
+
+    fn is_last_layer_algorithm(alg: &Algorithm) -> bool {
+        let cube = alg.cube();
+        cube.centers() == CenterMap::default() && is_f2l_solved(&relative_tiles(cube))
+    }
+
+    fn oriented_edges(case: Oll) -> usize {
+        let tiles = relative_tiles(case.algorithm().cube().inverse());
+        [1, 3, 5, 7]
+            .iter()
+            .filter(|&&i| tiles[i] == Some(Face::Up))
+            .count()
+    }
+
+    fn oriented_corners(case: Oll) -> usize {
+        let tiles = relative_tiles(case.algorithm().cube().inverse());
+        [0, 2, 6, 8]
+            .iter()
+            .filter(|&&i| tiles[i] == Some(Face::Up))
+            .count()
+    }
+
+    #[test]
+    fn oll_algorithms() {
+        for case in Oll::cases() {
+            let alg = case.algorithm();
+            assert!(is_last_layer_algorithm(&alg), "{}", case);
+            let state = alg.cube().inverse();
+            let found = recognize_oll(state).unwrap();
+            assert_eq!(found.case, Some(case));
+            assert_eq!(found.pre_auf, None);
+        }
+        // The shapes of the well known groups of cases.
+        let dots = [1, 2, 3, 4, 17, 18, 19, 20];
+        let crosses = [21, 22, 23, 24, 25, 26, 27];
+        for case in Oll::cases() {
+            let edges = oriented_edges(case);
+            assert_eq!(edges == 0, dots.contains(&case.number()), "{}", case);
+            assert_eq!(edges == 4, crosses.contains(&case.number()), "{}", case);
+        }
+        for &number in &[20, 28, 57] {
+            assert_eq!(oriented_corners(Oll::new(number).unwrap()), 4);
+        }
+    }
+
+    #[test]
+    fn pll_algorithms() {
+        for &case in &Pll::ALL {
+            let alg = case.algorithm();
+            assert!(is_last_layer_algorithm(&alg), "{}", case);
+            let state = alg.cube().inverse();
+            assert!(recognize_oll(state).unwrap().case.is_none());
+            let found = recognize_pll(state).unwrap();
+            assert_eq!(found.case, Some(case));
+            assert!((state * found.algorithm()).is_solved());
+        }
+    }
+
+    #[test]
+    fn coll_algorithms() {
+        let mut names = Vec::new();
+        for case in Coll::cases() {
+            let alg = case.algorithm();
+            assert!(is_last_layer_algorithm(&alg), "{}", case);
+            let state = alg.cube().inverse();
+            assert_eq!(recognize_oll(state).unwrap().case, case.oll(), "{}", case);
+            let found = recognize_coll(state).unwrap();
+            assert_eq!(found.case, Some(case));
+            assert_eq!((found.pre_auf, found.post_auf), (None, None));
+            names.push(case.to_string());
+        }
+        // Two cases with oriented corners, four for the symmetric H and six
+        // for each other cross OLL.
+        for shape in std::iter::once(None).chain((21..=27).map(Oll::new)) {
+            let count = Coll::cases().filter(|case| case.oll() == shape).count();
+            let expected = match shape.map(Oll::number) {
+                None => 2,
+                Some(21) => 4,
+                Some(_) => 6,
+            };
+            assert_eq!(count, expected, "{:?}", shape);
+        }
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), 42);
+    }
+
+    /// Every state reached from the solved cube by the generators, told
+    /// apart by `key`.
+    fn states<K: Eq + std::hash::Hash>(generators: &[Cube], key: impl Fn(Cube) -> K) -> Vec<Cube> {
+        let mut seen = std::collections::HashSet::new();
+        seen.insert(key(Cube::default()));
+        let mut states = vec![Cube::default()];
+        let mut next = 0;
+        while next < states.len() {
+            let cube = states[next];
+            next += 1;
+            for &generator in generators {
+                let state = cube * generator;
+                if seen.insert(key(state)) {
+                    states.push(state);
+                }
+            }
+        }
+        states
+    }
+
+    #[test]
+    fn recognizes_every_last_layer() {
+        let sune = Oll::new(27).unwrap().algorithm().cube();
+        let [a_perm, u_perm, t_perm] =
+            [Pll::Aa, Pll::Ua, Pll::T].map(|case| case.algorithm().cube());
+        let flip = Oll::new(57).unwrap().algorithm().cube();
+
+        // All 27 corner twists and 8 edge flips.
+        let orientations = states(&[Move::U1.cube(), sune, flip], |cube| {
+            orientation_mask(&relative_tiles(cube))
+        });
+        assert_eq!(orientations.len(), 216);
+        for &cube in &orientations {
+            let oriented = cube * recognize_oll(cube).unwrap().algorithm();
+            assert_eq!(recognize_oll(oriented).unwrap().case, None);
+        }
+
+        // All even permutations of the 4 corners and 4 edges, with the
+        // turns of the upper face.
+        let permutations = states(&[Move::U1.cube(), a_perm, u_perm, t_perm], relative_tiles);
+        assert_eq!(permutations.len(), 288);
+        for &cube in &permutations {
+            assert!((cube * recognize_pll(cube).unwrap().algorithm()).is_solved());
+        }
+
+        // All 24 permutations and 27 twists of the corners, whatever the
+        // edges are.
+        let corners = states(&[Move::U1.cube(), sune, t_perm], |cube| {
+            corner_tiles(&relative_tiles(cube))
+        });
+        assert_eq!(corners.len(), 648);
+        for &cube in &corners {
+            let cube = cube * recognize_coll(cube).unwrap().algorithm();
+            let pll = recognize_pll(cube).unwrap().case;
+            assert!(
+                matches!(pll, None | Some(Pll::Ua | Pll::Ub | Pll::H | Pll::Z)),
+                "{:?}",
+                pll
+            );
+        }
+    }
+
+    #[test]
+    fn recognizes_any_last_layer() {
+        let mut rng = oorandom::Rand32::new(15);
+        let holds = [&[][..], &[Move::Y1], &[Move::Y2, Move::X2, Move::Z2]];
+        for _ in 0..100 {
+            let mut cube = Cube::default();
+            for &mv in holds[rng.rand_range(0..3) as usize] {
+                cube *= mv;
+            }
+            for _ in 0..3 {
+                let case = Oll::new(rng.rand_range(1..58) as u8).unwrap();
+                cube *= auf_cube(AUFS[rng.rand_range(0..4) as usize]);
+                cube = cube * case.algorithm();
+            }
+            let pll = Pll::ALL[rng.rand_range(0..21) as usize];
+            cube = cube * pll.algorithm() * Move::U1;
+
+            let oll = recognize_oll(cube).unwrap();
+            cube = cube * oll.algorithm();
+            assert!(recognize_oll(cube).unwrap().case.is_none());
+            let pll = recognize_pll(cube).unwrap();
+            cube = cube * pll.algorithm();
+            assert!(FixedCentersCube::from(cube).is_solved());
+        }
+        let tilted = Cube::default() * Move::R1;
+        assert_eq!(recognize_oll(tilted), Err(RecognitionError::UnsolvedF2L));
+        let upside_down = Cube::default() * Move::X2 * Move::R1 * Move::U1 * Move::R3;
+        assert_eq!(
+            recognize_oll(upside_down),
+            Err(RecognitionError::UnsolvedF2L)
+        );
+        let sune = Cube::default() * Oll::new(27).unwrap().algorithm();
+        assert_eq!(
+            recognize_pll(sune),
+            Err(RecognitionError::UnorientedLastLayer)
+        );
+        let flipped = Cube::default() * Oll::new(45).unwrap().algorithm();
+        assert_eq!(
+            recognize_coll(flipped),
+            Err(RecognitionError::UnorientedEdges)
+        );
+
+        // States no sequence of moves reaches: two edges swapped by their
+        // stickers, and a corner twisted in place.
+        let mut swapped = TileMap::from(Cube::default());
+        swapped.as_array_mut().swap(19, 37);
+        let swapped = swapped.cube().unwrap();
+        let twist = (CornerOrientation::Cw as u64) << (Corner::URF as u64 * 8 + 3);
+        let corners = unsafe { CornerMap::from_raw_unchecked(CornerMap::default().raw() | twist) };
+        let twisted = Cube::new(CenterMap::default(), corners, EdgeMap::default());
+        for &cube in &[swapped, twisted] {
+            let unsolvable = RecognitionError::Unsolvable;
+            assert_eq!(recognize_oll(cube).unwrap_err(), unsolvable);
+            assert_eq!(recognize_pll(cube).unwrap_err(), unsolvable);
+            assert_eq!(recognize_coll(cube).unwrap_err(), unsolvable);
+        }
+    }
+}
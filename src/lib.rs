@@ -11,12 +11,32 @@
 //! - **Compact 16-byte** representation of the entire cube state.
 //! - **Ergonomic immutable & [`Copy`]-based API.**
 //! - **Two renderers**: SVG and ANSI terminal via [`CubeDisplay`].
-//! - **Two-phase Kociemba [solver]**: finds near-optimal solutions.
+//! - **Two-phase Kociemba [solver]**: finds near-optimal solutions, or
+//!   provably optimal ones for states close to solved with
+//!   [`Solver::search_optimal`], or a stream of ever shorter ones with
+//!   [`Solver::solutions`], or of only some stickers with
+//!   [`Solver::search_partial`].
+//! - **Parallel [batch solving](Solver::solve_batch)** with pruning tables
+//!   built once and shared between threads, see [`SolverTables`].
+//! - **Random-state [scrambles](Scrambler)** following WCA conventions.
+//! - **Optional [serde](https://serde.rs) support** via the `serde` feature.
 //! - **Group-theoretic model**:
 //!   - Cube states are permutation maps composed via multiplication.
 //!   - Inverse and identity ([`Cube::default`]) elements.
+//! - **[Algorithm] parsing** of move sequences, including commutators,
+//!   conjugates and repetitions.
+//! - **[Last layer](last_layer) recognition** of the OLL, PLL and COLL
+//!   cases, with a verified algorithm for each.
+//! - **[CFOP](cfop) reconstructions**: splitting a solve into its cross,
+//!   F2L pairs, OLL and PLL.
+//! - **Optimal [cross](cross) solutions** for any color, or the shortest
+//!   cross among all colors.
+//! - **Optimal [F2L](f2l) pairs and XCrosses**, optionally restricted to a
+//!   subset of the faces.
 //! - **Move manipulation**: inverse, projection, decomposition into
 //!   axis / rotation / kind, and construction from components.
+//! - **[Symmetries](Symmetry)**: conjugation by the 48 rotations and
+//!   reflections of the cube.
 //!
 //! # Examples
 //!
@@ -46,12 +66,23 @@
 //! assert!(cube.is_solved());
 //! ```
 //#![doc(html_logo_url = "path_to_logo", html_favicon_url = "path_to_favicon")]
+pub mod algorithm;
+pub mod cfop;
+pub mod cross;
 pub mod cube;
 mod cubedisplay;
+pub mod f2l;
+pub mod last_layer;
 pub mod moves;
+pub mod scramble;
+#[cfg(feature = "serde")]
+mod serde_impl;
 mod solver;
+pub mod symmetry;
 pub mod tile;
 
+#[doc(inline)]
+pub use algorithm::Algorithm;
 #[doc(inline)]
 pub use cube::corner::{COIndex, CPIndex, Corner, CornerOrientation};
 #[doc(inline)]
@@ -62,7 +93,13 @@ pub use cubedisplay::CubeDisplay;
 pub use moves::FaceMove;
 #[doc(inline)]
 pub use moves::Move;
-pub use solver::Solver;
+#[doc(inline)]
+pub use scramble::Scrambler;
+pub use solver::{
+    Invariant, Solutions, SolveError, Solver, SolverOptions, SolverTables, SolverWorker,
+};
+#[doc(inline)]
+pub use symmetry::Symmetry;
 
 #[doc(inline)]
 pub use cube::corner::CornerMap;
@@ -74,7 +111,11 @@ pub use cube::center::CenterMap;
 #[doc(inline)]
 pub use cube::Cube;
 #[doc(inline)]
+pub use cube::{CornerCycle, EdgeCycle};
+#[doc(inline)]
 pub use cube::FixedCentersCube;
+#[doc(inline)]
+pub use cube::RandomSource;
 use std::mem;
 #[doc(inline)]
 pub use tile::TileMap;
@@ -86,6 +127,18 @@ pub enum MapError {
     OutOfBounds,
 }
 
+impl std::fmt::Display for MapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            MapError::Orientation => "invalid total orientation of pieces",
+            MapError::Duplicate => "piece appears more than once",
+            MapError::OutOfBounds => "piece or orientation out of bounds",
+        })
+    }
+}
+
+impl std::error::Error for MapError {}
+
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub enum PieceKind {
     Edge,
@@ -114,6 +167,28 @@ impl std::convert::TryFrom<u8> for Face {
         }
     }
 }
+/// Formats the face as its single letter symbol, e.g. `U`.
+impl std::fmt::Display for Face {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(["U", "D", "F", "B", "R", "L"][*self as usize])
+    }
+}
+
+impl std::str::FromStr for Face {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "U" => Ok(Face::Up),
+            "D" => Ok(Face::Down),
+            "F" => Ok(Face::Front),
+            "B" => Ok(Face::Back),
+            "R" => Ok(Face::Right),
+            "L" => Ok(Face::Left),
+            _ => Err("Unknown face symbol."),
+        }
+    }
+}
+
 impl Face {
     /// An iterator of the 6 faces.
     pub fn faces() -> impl Iterator<Item = Face> {
@@ -28,6 +28,11 @@ use crate::CenterMap;
 use crate::{CornerMap, Cube, EdgeMap, Face, FixedCentersCube};
 
 use std::mem;
+
+mod metric;
+mod simplify;
+pub use self::metric::Metric;
+pub use self::simplify::{denormalize, normalize, simplify, simplify_with};
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Move {
     U1,
@@ -228,12 +233,70 @@ pub static MOVE_TABLE: &[Cube; 54] = &[
     Cube::from_raw_unchecked(0x0D17140E03820160, 0x0CE35B39801188A4), //Lw3
 ];
 use std::str::FromStr;
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MoveParseError {
     MissingTurnCharacter,
     UnexpectedWide,
     UnknownSymbol,
 }
+
+impl std::fmt::Display for MoveParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            MoveParseError::MissingTurnCharacter => "missing turn character",
+            MoveParseError::UnexpectedWide => "unexpected wide modifier",
+            MoveParseError::UnknownSymbol => "unknown move symbol",
+        })
+    }
+}
+
+impl std::error::Error for MoveParseError {}
+
+/// Formats the move in standard notation, e.g. `R`, `U2`, `Fw'`, `M` or `x`.
+///
+/// Rotations are written in lower case, and the output can be parsed back
+/// with [Move::from_str].
+/// ```
+/// use cubie::Move;
+/// for mv in Move::moves() {
+///     assert_eq!(mv.to_string().parse::<Move>().unwrap(), mv);
+/// }
+/// assert_eq!(Move::Rw3.to_string(), "Rw'");
+/// assert_eq!(Move::X2.to_string(), "x2");
+/// ```
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = [
+            "U", "D", "F", "B", "R", "L", "E", "S", "M", "y", "z", "x", "Uw", "Dw", "Fw", "Bw",
+            "Rw", "Lw",
+        ][*self as usize / 3];
+        f.write_str(symbol)?;
+        match self.angle() {
+            MoveAngle::Cw => Ok(()),
+            MoveAngle::Two => f.write_str("2"),
+            MoveAngle::Ccw => f.write_str("'"),
+        }
+    }
+}
+
+impl std::fmt::Display for FaceMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Move::from(*self).fmt(f)
+    }
+}
+
+impl FromStr for FaceMove {
+    type Err = MoveParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mv = Move::from_str(s)?;
+        if mv.kind() == MoveKind::Face {
+            Ok(FaceMove::new(mv.face(), mv.angle()))
+        } else {
+            Err(MoveParseError::UnknownSymbol)
+        }
+    }
+}
 impl FromStr for Move {
     type Err = MoveParseError;
 
@@ -249,9 +312,9 @@ impl FromStr for Move {
             'E' => Move::E1,
             'M' => Move::M1,
             'S' => Move::S1,
-            'X' => Move::X1,
-            'Y' => Move::Y1,
-            'Z' => Move::Z1,
+            'X' | 'x' => Move::X1,
+            'Y' | 'y' => Move::Y1,
+            'Z' | 'z' => Move::Z1,
             'u' => Move::Uw1,
             'd' => Move::Dw1,
             'f' => Move::Fw1,
@@ -272,7 +335,7 @@ impl FromStr for Move {
             b"1" => Ok(base),
             b"3" => Ok(base.ccw()),
             b"'" => Ok(base.ccw()),
-            b"2" => Ok(base.two()),
+            b"2" | b"2'" => Ok(base.two()),
             b"w" => Ok(base.two()),
             _ => Err(MoveParseError::UnknownSymbol),
         }
@@ -332,6 +395,16 @@ impl MoveAngle {
             MoveAngle::Ccw => PI / 2.0,
         }
     }
+
+    /// The angle turning the opposite way.
+    #[inline]
+    pub fn inverse(self) -> MoveAngle {
+        match self {
+            MoveAngle::Cw => MoveAngle::Ccw,
+            MoveAngle::Two => MoveAngle::Two,
+            MoveAngle::Ccw => MoveAngle::Cw,
+        }
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -0,0 +1,149 @@
+//! Move count metrics.
+use super::{Move, MoveAngle, MoveKind};
+
+/// The standard metrics for counting the length of a move sequence.
+///
+/// | Move          | HTM | QTM | STM | ETM | QSTM |
+/// |---------------|-----|-----|-----|-----|------|
+/// | `R`, `Rw`     | 1   | 1   | 1   | 1   | 1    |
+/// | `R2`, `Rw2`   | 1   | 2   | 1   | 1   | 2    |
+/// | `M`           | 2   | 2   | 1   | 1   | 1    |
+/// | `M2`          | 2   | 4   | 1   | 1   | 2    |
+/// | `x`           | 0   | 0   | 0   | 1   | 0    |
+/// | `x2`          | 0   | 0   | 0   | 1   | 0    |
+///
+/// Wide moves turn a single block of layers and cost the same as the face
+/// move of the same angle. Rotations only count in ETM, unless counted
+/// explicitly with [Metric::count_including_rotations].
+///
+/// # Example
+/// ```
+/// use cubie::moves::Metric;
+/// use cubie::Move::*;
+///
+/// let moves = [R1, U2, M3, Rw1, Y1];
+/// assert_eq!(Metric::Htm.count(&moves), 5);
+/// assert_eq!(Metric::Qtm.count(&moves), 6);
+/// assert_eq!(Metric::Stm.count(&moves), 4);
+/// assert_eq!(Metric::Etm.count(&moves), 5);
+/// assert_eq!(Metric::Qstm.count(&moves), 5);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// Half turn metric: any turn of an outer block of layers is one move.
+    Htm,
+    /// Quarter turn metric: any quarter turn of an outer block of layers is
+    /// one move.
+    Qtm,
+    /// Slice turn metric: any turn of any block of layers is one move.
+    Stm,
+    /// Execution turn metric: every move, including rotations, is one move.
+    Etm,
+    /// Quarter slice turn metric: any quarter turn of any block of layers is
+    /// one move.
+    Qstm,
+}
+
+impl Metric {
+    /// All the metrics.
+    pub const ALL: [Metric; 5] = [
+        Metric::Htm,
+        Metric::Qtm,
+        Metric::Stm,
+        Metric::Etm,
+        Metric::Qstm,
+    ];
+
+    /// The cost of a single move.
+    pub fn cost(self, mv: Move) -> u32 {
+        if mv.kind() == MoveKind::Rotation && self != Metric::Etm {
+            0
+        } else {
+            self.layer_cost(mv)
+        }
+    }
+
+    /// The cost of a move as a turn of layers, ignoring that rotations do not
+    /// turn any layer relative to the others.
+    fn layer_cost(self, mv: Move) -> u32 {
+        let quarters = if mv.angle() == MoveAngle::Two { 2 } else { 1 };
+        let slice = mv.kind() == MoveKind::Slice;
+        match self {
+            Metric::Htm if slice => 2,
+            Metric::Qtm if slice => 2 * quarters,
+            Metric::Htm | Metric::Stm | Metric::Etm => 1,
+            Metric::Qtm | Metric::Qstm => quarters,
+        }
+    }
+
+    /// The length of a move sequence.
+    pub fn count(self, moves: &[Move]) -> u32 {
+        moves.iter().map(|&mv| self.cost(mv)).sum()
+    }
+
+    /// The length of a move sequence, with rotations costing as much as the
+    /// face move of the same angle.
+    ///
+    /// ```
+    /// use cubie::moves::Metric;
+    /// use cubie::Move::*;
+    ///
+    /// assert_eq!(Metric::Qtm.count_including_rotations(&[R1, X2]), 3);
+    /// ```
+    pub fn count_including_rotations(self, moves: &[Move]) -> u32 {
+        moves.iter().map(|&mv| self.layer_cost(mv)).sum()
+    }
+}
+
+impl std::fmt::Display for Metric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Metric::Htm => "HTM",
+            Metric::Qtm => "QTM",
+            Metric::Stm => "STM",
+            Metric::Etm => "ETM",
+            Metric::Qstm => "QSTM",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Move::*;
+
+    #[test]
+    fn move_costs() {
+        let table: &[(Move, [u32; 5])] = &[
+            (R1, [1, 1, 1, 1, 1]),
+            (R2, [1, 2, 1, 1, 2]),
+            (R3, [1, 1, 1, 1, 1]),
+            (Rw2, [1, 2, 1, 1, 2]),
+            (M1, [2, 2, 1, 1, 1]),
+            (M2, [2, 4, 1, 1, 2]),
+            (E3, [2, 2, 1, 1, 1]),
+            (X1, [0, 0, 0, 1, 0]),
+            (Y2, [0, 0, 0, 1, 0]),
+        ];
+        for &(mv, costs) in table {
+            for (metric, &cost) in Metric::ALL.iter().zip(costs.iter()) {
+                assert_eq!(metric.cost(mv), cost, "{} {}", metric, mv);
+            }
+        }
+    }
+
+    #[test]
+    fn slices_match_face_turns() {
+        // A slice is two face turns and a rotation, and HTM/QTM agree.
+        for mv in Move::moves().filter(|mv| mv.kind() == MoveKind::Slice) {
+            let face = mv.face();
+            let faces = [
+                Move::new(MoveKind::Face, face.opposite(), mv.angle()),
+                Move::new(MoveKind::Face, face, mv.angle().inverse()),
+            ];
+            for &metric in &[Metric::Htm, Metric::Qtm] {
+                assert_eq!(metric.cost(mv), metric.count(&faces));
+            }
+        }
+    }
+}
@@ -0,0 +1,402 @@
+//! Cancellation and merging of move sequences, and conversion between
+//! face turns and the other kinds of moves.
+use super::{FaceMove, Move, MoveAngle, MoveKind};
+use crate::{CenterMap, Face};
+use std::collections::VecDeque;
+
+/// Quarter turns of the three layers of an axis, from the U, F or R layer
+/// through the middle layer to the opposite face, all measured clockwise
+/// as seen from the U, F or R face.
+type Layers = [u8; 3];
+
+const AXIS_FACES: [Face; 3] = [Face::Up, Face::Front, Face::Right];
+
+fn layers_index(layers: Layers) -> usize {
+    (layers[0] + 4 * layers[1] + 16 * layers[2]) as usize
+}
+
+fn add_layers(a: Layers, b: Layers) -> Layers {
+    [(a[0] + b[0]) % 4, (a[1] + b[1]) % 4, (a[2] + b[2]) % 4]
+}
+
+/// The axis a move turns about, and the layers it turns.
+fn axis_layers(mv: Move) -> (usize, Layers) {
+    let face = mv.face() as u8;
+    let quarters = mv.angle() as u8 + 1;
+    let turn = if face & 1 == 0 {
+        quarters
+    } else {
+        4 - quarters
+    };
+    let outer = if face & 1 == 0 {
+        [turn, 0, 0]
+    } else {
+        [0, 0, turn]
+    };
+    let layers = match mv.kind() {
+        MoveKind::Face => outer,
+        MoveKind::Slice => [0, turn, 0],
+        MoveKind::Wide => [outer[0], turn, outer[2]],
+        MoveKind::Rotation => [turn; 3],
+    };
+    ((face >> 1) as usize, layers)
+}
+
+/// The shortest sequence of moves of the given kinds for every combination
+/// of layer turns about an axis, indexed by [layers_index].
+///
+/// Face moves are always available. Among sequences of equal length, face
+/// moves are preferred over slice, wide and rotation moves, in that order.
+fn shortest_forms(axis: usize, kinds: &[MoveKind]) -> Vec<Option<Vec<Move>>> {
+    let face = AXIS_FACES[axis];
+    let mut candidates = Vec::new();
+    for &kind in &[
+        MoveKind::Face,
+        MoveKind::Slice,
+        MoveKind::Wide,
+        MoveKind::Rotation,
+    ] {
+        if kind != MoveKind::Face && !kinds.contains(&kind) {
+            continue;
+        }
+        let faces = match kind {
+            MoveKind::Face | MoveKind::Wide => vec![face, face.opposite()],
+            MoveKind::Slice | MoveKind::Rotation => vec![face],
+        };
+        for face in faces {
+            for &angle in &[MoveAngle::Cw, MoveAngle::Two, MoveAngle::Ccw] {
+                candidates.push(Move::new(kind, face, angle));
+            }
+        }
+    }
+
+    let mut forms = vec![None; 64];
+    forms[0] = Some(Vec::new());
+    let mut queue = VecDeque::new();
+    queue.push_back([0; 3]);
+    while let Some(layers) = queue.pop_front() {
+        for &mv in &candidates {
+            let next = add_layers(layers, axis_layers(mv).1);
+            if forms[layers_index(next)].is_none() {
+                let mut form: Vec<Move> = forms[layers_index(layers)].clone().unwrap();
+                form.push(mv);
+                forms[layers_index(next)] = Some(form);
+                queue.push_back(next);
+            }
+        }
+    }
+    for form in forms.iter_mut().flatten() {
+        form.sort_by_key(|mv| candidates.iter().position(|c| c == mv));
+    }
+    forms
+}
+
+/// The shortest sequence of rotations with the given effect on the centers.
+fn rotation_moves(centers: CenterMap) -> Vec<Move> {
+    let rotations: Vec<Move> = Move::moves()
+        .filter(|mv| mv.kind() == MoveKind::Rotation)
+        .collect();
+    if centers == CenterMap::default() {
+        return Vec::new();
+    }
+    for &a in &rotations {
+        if a.centers() == centers {
+            return vec![a];
+        }
+    }
+    for &a in &rotations {
+        for &b in &rotations {
+            if a.centers() * b.centers() == centers {
+                return vec![a, b];
+            }
+        }
+    }
+    unreachable!("every rotation is at most two rotation moves")
+}
+
+/// Simplifies a move sequence, writing the result only with the kinds of
+/// moves that appear in the input.
+///
+/// See [simplify_with] for the rules applied.
+///
+/// # Example
+/// ```
+/// use cubie::moves::simplify;
+/// use cubie::Move::*;
+///
+/// assert_eq!(simplify(&[R1, R1]), vec![R2]);
+/// assert_eq!(simplify(&[R1, U1, U3, R3]), vec![]);
+/// assert_eq!(simplify(&[R1, L1, R3]), vec![L1]);
+/// assert_eq!(simplify(&[Rw1, R3]), vec![R3, Rw1]);
+/// ```
+pub fn simplify(moves: &[Move]) -> Vec<Move> {
+    let mut kinds = Vec::new();
+    for mv in moves {
+        if !kinds.contains(&mv.kind()) {
+            kinds.push(mv.kind());
+        }
+    }
+    simplify_with(moves, &kinds)
+}
+
+/// Simplifies a move sequence, writing the result with face moves and the
+/// given kinds of moves only. The resulting [Cube](crate::Cube) is unchanged.
+///
+/// Consecutive moves about the same axis commute, so each run of them is
+/// merged into the turns of the three layers of that axis, and written back
+/// with the fewest moves. Runs that cancel completely are removed, which lets
+/// the runs around them merge in turn: `R U U' R'` cancels entirely and
+/// `R L R'` becomes `L`. Among forms of equal length, face moves are preferred,
+/// then slice, wide and rotation moves.
+///
+/// When only [MoveKind::Face] is allowed, middle layer turns are replaced by
+/// face turns and a change of orientation. The orientation changes are carried
+/// to the end of the sequence, which then finishes with the rotations needed
+/// to keep the resulting cube identical.
+///
+/// # Example
+/// ```
+/// use cubie::moves::{simplify_with, MoveKind};
+/// use cubie::{Cube, Move::*};
+///
+/// // Every kind of move allowed: R L' x' is a single slice move.
+/// let all = [MoveKind::Slice, MoveKind::Wide, MoveKind::Rotation];
+/// assert_eq!(simplify_with(&[R1, L3, X3], &all), vec![M1]);
+///
+/// // Face moves only, with the rotation postponed to the end.
+/// let moves = [M1, U1, M3];
+/// let faces = simplify_with(&moves, &[MoveKind::Face]);
+/// assert_eq!(faces, vec![R1, L3, B1, R3, L1]);
+/// assert_eq!(Cube::from(&faces), Cube::from(&moves));
+/// ```
+pub fn simplify_with(moves: &[Move], kinds: &[MoveKind]) -> Vec<Move> {
+    let forms = all_forms(kinds);
+    if kinds.iter().all(|&kind| kind == MoveKind::Face) {
+        let (faces, rotation) = normalize(moves);
+        let runs = merge_runs(faces.into_iter().map(Move::from));
+        let mut out = write_runs(&runs, &forms);
+        out.extend(rotation_moves(rotation));
+        out
+    } else {
+        write_runs(&merge_runs(moves.iter().copied()), &forms)
+    }
+}
+
+fn all_forms(kinds: &[MoveKind]) -> [Vec<Option<Vec<Move>>>; 3] {
+    [
+        shortest_forms(0, kinds),
+        shortest_forms(1, kinds),
+        shortest_forms(2, kinds),
+    ]
+}
+
+/// Merges consecutive moves about the same axis, dropping runs that cancel.
+fn merge_runs(moves: impl Iterator<Item = Move>) -> Vec<(usize, Layers)> {
+    let mut runs: Vec<(usize, Layers)> = Vec::new();
+    for mv in moves {
+        let (axis, mut layers) = axis_layers(mv);
+        match runs.last() {
+            Some(&(top, run)) if top == axis => {
+                layers = add_layers(run, layers);
+                runs.pop();
+            }
+            _ => (),
+        }
+        if layers != [0; 3] {
+            runs.push((axis, layers));
+        }
+    }
+    runs
+}
+
+fn write_runs(runs: &[(usize, Layers)], forms: &[Vec<Option<Vec<Move>>>; 3]) -> Vec<Move> {
+    let mut out = Vec::new();
+    for &(axis, layers) in runs {
+        out.extend(forms[axis][layers_index(layers)].as_ref().unwrap());
+    }
+    out
+}
+
+fn face_move(face: Face, quarters: u8) -> Option<FaceMove> {
+    match quarters % 4 {
+        0 => None,
+        1 => Some(FaceMove::new(face, MoveAngle::Cw)),
+        2 => Some(FaceMove::new(face, MoveAngle::Two)),
+        _ => Some(FaceMove::new(face, MoveAngle::Ccw)),
+    }
+}
+
+/// Rewrites a move sequence with outer face turns only, as performed on a
+/// cube held in a fixed orientation.
+///
+/// Each slice, wide move and rotation is replaced by the face turns it
+/// performs relative to the centers, and the orientation change is carried
+/// to the end of the sequence. Returns the face turns and the final
+/// orientation of the centers, so that applying the face turns and then
+/// rotating the whole cube to that orientation gives the original state.
+/// No moves are merged or cancelled, see [simplify] for that.
+///
+/// # Example
+/// ```
+/// use cubie::moves::{normalize, FaceMove};
+/// use cubie::{Cube, FixedCentersCube, Move::*};
+///
+/// let moves = [Rw1, U1, M2, Y1];
+/// let (faces, rotation) = normalize(&moves);
+/// assert_eq!(faces, vec![FaceMove::L1, FaceMove::F1, FaceMove::R2, FaceMove::L2]);
+///
+/// let cube = Cube::from(&moves);
+/// assert_eq!(cube.centers(), rotation);
+/// let fixed = faces.iter().fold(FixedCentersCube::default(), |cube, &mv| cube * mv);
+/// assert_eq!(FixedCentersCube::from(cube), fixed);
+/// ```
+pub fn normalize(moves: &[Move]) -> (Vec<FaceMove>, CenterMap) {
+    let mut rotation = CenterMap::default();
+    let mut faces = Vec::with_capacity(moves.len());
+    for &mv in moves {
+        let mv = mv.projection(rotation.inverse());
+        rotation = mv.centers() * rotation;
+        let (axis, [outer, middle, inner]) = axis_layers(mv);
+        let face = AXIS_FACES[axis];
+        faces.extend(face_move(face, outer + 4 - middle));
+        faces.extend(face_move(face.opposite(), middle + 4 - inner));
+    }
+    (faces, rotation)
+}
+
+/// Reverses [normalize]: rewrites face turns on a fixed-center cube, followed
+/// by a final orientation of the centers, with face moves and the given
+/// kinds of moves.
+///
+/// Consecutive turns about the same axis are merged as in [simplify_with],
+/// and written with the fewest moves allowed, now also allowing a turn of
+/// the middle layer. Pairs of opposite face turns become slice moves when
+/// [MoveKind::Slice] is allowed, for example. Moves that follow a change of
+/// orientation are relabelled for the new orientation, and the sequence ends
+/// with the rotations still needed to reach `rotation`.
+///
+/// # Example
+/// ```
+/// use cubie::moves::{denormalize, normalize, MoveKind};
+/// use cubie::{Cube, Move::*};
+///
+/// let moves = [R1, L3, U1, R3, L1];
+/// let (faces, rotation) = normalize(&moves);
+/// let slices = denormalize(&faces, rotation, &[MoveKind::Slice]);
+/// assert_eq!(slices, vec![M1, F1, M3]);
+/// assert_eq!(Cube::from(&slices), Cube::from(&moves));
+/// ```
+pub fn denormalize(faces: &[FaceMove], rotation: CenterMap, kinds: &[MoveKind]) -> Vec<Move> {
+    let forms = all_forms(kinds);
+    let mut frame = CenterMap::default();
+    let mut out = Vec::new();
+    for (axis, [outer, _, inner]) in merge_runs(faces.iter().map(|&mv| Move::from(mv))) {
+        // Any turn of the middle layer can be absorbed into the orientation.
+        let form = (0..4)
+            .filter_map(|middle| {
+                let layers = [(outer + middle) % 4, middle, (inner + middle) % 4];
+                forms[axis][layers_index(layers)].as_ref()
+            })
+            .min_by_key(|form| form.len())
+            .unwrap();
+        for &mv in form {
+            let mv = mv.projection(frame);
+            frame *= mv;
+            out.push(mv);
+        }
+    }
+    out.extend(rotation_moves(frame.inverse() * rotation));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cube;
+    use crate::Move::*;
+
+    fn random_moves(rng: &mut oorandom::Rand32, len: usize) -> Vec<Move> {
+        (0..len)
+            .map(|_| {
+                let index = rng.rand_range(0..Move::COUNT as u32) as u8;
+                std::convert::TryFrom::try_from(index).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cancellations() {
+        assert_eq!(simplify(&[]), vec![]);
+        assert_eq!(simplify(&[R1, R3]), vec![]);
+        assert_eq!(simplify(&[R1, R1, R1]), vec![R3]);
+        assert_eq!(simplify(&[L1, R1, L3]), vec![R1]);
+        assert_eq!(simplify(&[F1, R1, U1, U3, R3, F1]), vec![F2]);
+        assert_eq!(simplify(&[U1, D1, U1]), vec![U2, D1]);
+        assert_eq!(simplify(&[M1, M1, X2]), vec![R2, L2]);
+        assert_eq!(simplify(&[Y1, U1, Y3]), vec![U1]);
+        assert_eq!(simplify(&[Rw1, L3]), vec![R1, Lw3]);
+        assert_eq!(simplify_with(&[Rw1, L3], &[MoveKind::Rotation]), vec![X1]);
+        assert_eq!(simplify(&[R1, U1, R3, U3]), vec![R1, U1, R3, U3]);
+    }
+
+    #[test]
+    fn normalize_round_trips() {
+        let mut rng = oorandom::Rand32::new(14);
+        let all = [
+            MoveKind::Face,
+            MoveKind::Slice,
+            MoveKind::Wide,
+            MoveKind::Rotation,
+        ];
+        for len in 0..40 {
+            let moves = random_moves(&mut rng, len);
+            let cube = Cube::from(&moves);
+            let (faces, rotation) = normalize(&moves);
+            let face_cube = Cube::from(faces.iter().map(|&mv| Move::from(mv)).collect::<Vec<_>>());
+            assert_eq!(face_cube * rotation.cube(), cube);
+            for kinds in &[&all[..1], &all[..2], &all[1..3], &all[3..], &all[..]] {
+                let moves = denormalize(&faces, rotation, kinds);
+                assert_eq!(Cube::from(&moves), cube);
+                let allowed = |mv: &Move| kinds.contains(&mv.kind()) || mv.kind() == MoveKind::Face;
+                let turns = moves.iter().take_while(|mv| allowed(mv)).count();
+                assert!(moves[turns..]
+                    .iter()
+                    .all(|mv| mv.kind() == MoveKind::Rotation));
+            }
+        }
+        let (faces, rotation) = normalize(&[X1, Y2]);
+        assert!(faces.is_empty());
+        let rotations = denormalize(&faces, rotation, &[]);
+        assert_eq!(rotations.len(), 2);
+        assert_eq!(Cube::from(&rotations), Cube::from(&[X1, Y2]));
+    }
+
+    #[test]
+    fn preserves_cube() {
+        let mut rng = oorandom::Rand32::new(11);
+        let all = [
+            MoveKind::Face,
+            MoveKind::Slice,
+            MoveKind::Wide,
+            MoveKind::Rotation,
+        ];
+        for len in 0..40 {
+            let moves = random_moves(&mut rng, len);
+            let cube = Cube::from(&moves);
+            let simplified = simplify(&moves);
+            assert_eq!(Cube::from(&simplified), cube);
+            assert!(simplified.len() <= moves.len());
+            assert_eq!(simplify(&simplified), simplified);
+            for kinds in &[&all[..1], &all[..2], &all[..3], &all[1..], &all[3..]] {
+                let simplified = simplify_with(&moves, kinds);
+                assert_eq!(Cube::from(&simplified), cube);
+            }
+            let faces = simplify_with(&moves, &[MoveKind::Face]);
+            assert!(faces.len() <= moves.len() * 2 + 2);
+            let turns = faces.iter().take_while(|mv| mv.kind() == MoveKind::Face);
+            assert!(faces[turns.count()..]
+                .iter()
+                .all(|mv| mv.kind() == MoveKind::Rotation));
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Random-state scramble generation.
+use crate::{FaceMove, FixedCentersCube, Move, Solver};
+use std::convert::TryFrom;
+
+/// Generates random-state scrambles in the style of the WCA scramble program.
+///
+/// Each scramble is produced by drawing a uniformly random state, solving it
+/// with the two-phase [`Solver`] and inverting the solution. Scrambles only
+/// contain outer face turns applied in the standard orientation (white top,
+/// green front in the usual colour scheme).
+///
+/// States that are solved or one move away from solved are rejected, as are
+/// scrambles shorter than [`Scrambler::min_length`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use cubie::{Cube, Scrambler};
+///
+/// let mut scrambler = Scrambler::new(0x5eed);
+/// let scramble = scrambler.scramble();
+/// assert!(!Cube::from(&scramble).is_solved());
+/// ```
+pub struct Scrambler {
+    solver: Solver,
+    rng: oorandom::Rand32,
+    min_length: usize,
+}
+
+impl Default for Scrambler {
+    /// Creates a scrambler seeded from the system clock.
+    fn default() -> Scrambler {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(0);
+        Scrambler::new(seed)
+    }
+}
+
+impl Scrambler {
+    /// Creates a scrambler with a fixed seed, producing the same sequence
+    /// of scrambles on every run.
+    pub fn new(seed: u64) -> Scrambler {
+        Scrambler::with_solver(Solver::default(), seed)
+    }
+
+    /// Creates a scrambler reusing an existing solver, avoiding a rebuild of
+    /// its pruning tables if they are already initialized.
+    pub fn with_solver(solver: Solver, seed: u64) -> Scrambler {
+        Scrambler {
+            solver,
+            rng: oorandom::Rand32::new(seed),
+            min_length: 2,
+        }
+    }
+
+    /// The minimum number of moves in a generated scramble, 2 by default.
+    pub fn min_length(&self) -> usize {
+        self.min_length
+    }
+
+    /// Sets the minimum number of moves in a generated scramble.
+    ///
+    /// Values above 20 should be avoided: random states almost never need
+    /// more than 20 moves, so generation would spin for a very long time.
+    pub fn set_min_length(&mut self, min_length: usize) {
+        self.min_length = min_length;
+    }
+
+    /// Consumes the scrambler, returning the underlying solver.
+    pub fn into_solver(self) -> Solver {
+        self.solver
+    }
+
+    /// Generates the next scramble.
+    ///
+    /// Pruning tables are built on the first call if the solver has not
+    /// been initialized yet.
+    pub fn scramble(&mut self) -> Vec<Move> {
+        self.scramble_state().1
+    }
+
+    /// Generates the next scramble, with the random state it reaches.
+    fn scramble_state(&mut self) -> (FixedCentersCube, Vec<Move>) {
+        loop {
+            let state = FixedCentersCube::random(&mut self.rng);
+            if !is_scramble_candidate(state) {
+                continue;
+            }
+            let solution = self.solver.search_face_moves(state);
+            if solution.len() < self.min_length {
+                continue;
+            }
+            let scramble = solution
+                .iter()
+                .rev()
+                .map(|mv| Move::from(mv.inverse()))
+                .collect();
+            return (state, scramble);
+        }
+    }
+}
+
+/// Returns false for states solvable in fewer than two moves.
+fn is_scramble_candidate(state: FixedCentersCube) -> bool {
+    !state.is_solved() && FaceMove::try_from(state).is_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cube;
+
+    #[test]
+    fn rejects_trivial_states() {
+        assert!(!is_scramble_candidate(FixedCentersCube::default()));
+        for mv in FaceMove::moves() {
+            assert!(!is_scramble_candidate(mv.fc_cube()));
+            assert!(is_scramble_candidate(
+                mv.fc_cube() * mv.face().opposite().cw()
+            ));
+        }
+    }
+
+    #[test]
+    fn scrambles_reach_their_state() {
+        let mut scrambler = Scrambler::new(17);
+        scrambler.set_min_length(16);
+        for _ in 0..3 {
+            let (state, scramble) = scrambler.scramble_state();
+            assert!(scramble.len() >= 16);
+            assert!(scramble
+                .iter()
+                .all(|mv| mv.kind() == crate::moves::MoveKind::Face));
+            let cube = Cube::from(&scramble);
+            assert!(!cube.is_solved());
+            assert!(cube.centers() == crate::CenterMap::default());
+            assert_eq!(FixedCentersCube::from(cube), state);
+        }
+    }
+}
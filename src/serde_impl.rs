@@ -0,0 +1,242 @@
+//! Serde support, enabled by the `serde` feature.
+//!
+//! Human-readable formats store moves in standard notation and maps as the
+//! facelet strings of [TileMap]. Binary formats store the compact raw words.
+//! Deserialized maps are always validated.
+use crate::tile::Tile;
+use crate::{CenterMap, CornerMap, Cube, EdgeMap, Face, FaceMove, FixedCentersCube, Move, TileMap};
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt::Display;
+
+/// The two serialized forms of a type.
+trait Repr: Sized {
+    type Raw: Serialize + DeserializeOwned;
+    fn to_raw(&self) -> Self::Raw;
+    fn from_raw(raw: Self::Raw) -> Result<Self, String>;
+    fn to_text(&self) -> String;
+    fn from_text(text: &str) -> Result<Self, String>;
+}
+
+fn describe(err: impl Display) -> String {
+    err.to_string()
+}
+
+macro_rules! impl_serde {
+    ($($ty:ty),*) => {$(
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    self.to_text().serialize(serializer)
+                } else {
+                    self.to_raw().serialize(serializer)
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                if deserializer.is_human_readable() {
+                    let text = String::deserialize(deserializer)?;
+                    <$ty>::from_text(&text).map_err(D::Error::custom)
+                } else {
+                    let raw = <$ty as Repr>::Raw::deserialize(deserializer)?;
+                    <$ty as Repr>::from_raw(raw).map_err(D::Error::custom)
+                }
+            }
+        }
+    )*};
+}
+
+impl_serde!(
+    Cube,
+    FixedCentersCube,
+    CornerMap,
+    EdgeMap,
+    CenterMap,
+    TileMap,
+    Move
+);
+impl_serde!(FaceMove, Face, Tile);
+
+impl Repr for Cube {
+    type Raw = (u64, u64);
+    fn to_raw(&self) -> Self::Raw {
+        self.raw()
+    }
+    fn from_raw((centered_corners, edges): Self::Raw) -> Result<Self, String> {
+        Cube::from_raw(centered_corners, edges).map_err(describe)
+    }
+    fn to_text(&self) -> String {
+        TileMap::from(*self).to_string()
+    }
+    fn from_text(text: &str) -> Result<Self, String> {
+        text.parse::<TileMap>()
+            .and_then(|tiles| tiles.cube())
+            .map_err(describe)
+    }
+}
+
+impl Repr for FixedCentersCube {
+    type Raw = (u64, u64);
+    fn to_raw(&self) -> Self::Raw {
+        self.raw()
+    }
+    fn from_raw((corners, edges): Self::Raw) -> Result<Self, String> {
+        Ok(FixedCentersCube::new(
+            CornerMap::from_raw(corners).map_err(describe)?,
+            EdgeMap::from_raw(edges).map_err(describe)?,
+        ))
+    }
+    fn to_text(&self) -> String {
+        TileMap::from(*self).to_string()
+    }
+    fn from_text(text: &str) -> Result<Self, String> {
+        text.parse::<TileMap>()
+            .and_then(|tiles| tiles.fc_cube())
+            .map_err(describe)
+    }
+}
+
+impl Repr for CornerMap {
+    type Raw = u64;
+    fn to_raw(&self) -> Self::Raw {
+        self.raw()
+    }
+    fn from_raw(raw: Self::Raw) -> Result<Self, String> {
+        CornerMap::from_raw(raw).map_err(describe)
+    }
+    fn to_text(&self) -> String {
+        TileMap::from(*self).to_string()
+    }
+    fn from_text(text: &str) -> Result<Self, String> {
+        text.parse::<TileMap>()
+            .and_then(|tiles| tiles.corners())
+            .map_err(describe)
+    }
+}
+
+impl Repr for EdgeMap {
+    type Raw = u64;
+    fn to_raw(&self) -> Self::Raw {
+        self.raw()
+    }
+    fn from_raw(raw: Self::Raw) -> Result<Self, String> {
+        EdgeMap::from_raw(raw).map_err(describe)
+    }
+    fn to_text(&self) -> String {
+        TileMap::from(*self).to_string()
+    }
+    fn from_text(text: &str) -> Result<Self, String> {
+        text.parse::<TileMap>()
+            .and_then(|tiles| tiles.edges())
+            .map_err(describe)
+    }
+}
+
+impl Repr for CenterMap {
+    type Raw = u64;
+    fn to_raw(&self) -> Self::Raw {
+        self.raw()
+    }
+    fn from_raw(raw: Self::Raw) -> Result<Self, String> {
+        CenterMap::from_raw(raw).map_err(describe)
+    }
+    fn to_text(&self) -> String {
+        TileMap::from(*self).to_string()
+    }
+    fn from_text(text: &str) -> Result<Self, String> {
+        text.parse::<TileMap>()
+            .and_then(|tiles| tiles.centers())
+            .map_err(describe)
+    }
+}
+
+impl Repr for TileMap {
+    type Raw = String;
+    fn to_raw(&self) -> Self::Raw {
+        self.to_string()
+    }
+    fn from_raw(raw: Self::Raw) -> Result<Self, String> {
+        TileMap::from_text(&raw)
+    }
+    fn to_text(&self) -> String {
+        self.to_string()
+    }
+    fn from_text(text: &str) -> Result<Self, String> {
+        text.parse().map_err(describe)
+    }
+}
+
+macro_rules! impl_symbol_repr {
+    ($($ty:ty),*) => {$(
+        impl Repr for $ty {
+            type Raw = u8;
+            fn to_raw(&self) -> Self::Raw {
+                *self as u8
+            }
+            fn from_raw(raw: Self::Raw) -> Result<Self, String> {
+                <$ty>::try_from(raw).map_err(describe)
+            }
+            fn to_text(&self) -> String {
+                self.to_string()
+            }
+            fn from_text(text: &str) -> Result<Self, String> {
+                text.parse().map_err(describe)
+            }
+        }
+    )*};
+}
+
+impl_symbol_repr!(Move, FaceMove, Face, Tile);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Move::*;
+
+    fn round_trip<T>(value: T)
+    where
+        T: Repr + Serialize + DeserializeOwned + PartialEq + std::fmt::Debug,
+    {
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(serde_json::from_str::<T>(&json).unwrap(), value);
+        assert_eq!(T::from_raw(value.to_raw()).unwrap(), value);
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let cube = Cube::from(&[R1, Uw2, X1, F3]);
+        round_trip(cube);
+        round_trip(FixedCentersCube::from(cube));
+        round_trip(cube.corners());
+        round_trip(cube.edges());
+        round_trip(cube.centers());
+        round_trip(TileMap::from(cube));
+        for mv in Move::moves() {
+            round_trip(mv);
+        }
+        round_trip(FaceMove::B2);
+        round_trip(Face::Left);
+        round_trip(Tile::R7);
+        assert_eq!(serde_json::to_string(&Rw3).unwrap(), "\"Rw'\"");
+        assert_eq!(serde_json::to_string(&Tile::U1).unwrap(), "\"U1\"");
+        assert_eq!(
+            serde_json::to_string(&Cube::default()).unwrap(),
+            format!("\"{}\"", TileMap::from(Cube::default()))
+        );
+    }
+
+    #[test]
+    fn invalid_maps_are_rejected() {
+        let mut tiles = TileMap::from(Cube::default());
+        tiles[Tile::U1] = Some(Face::Down);
+        tiles[Tile::D1] = Some(Face::Up);
+        let json = serde_json::to_string(&tiles).unwrap();
+        assert!(serde_json::from_str::<Cube>(&json).is_err());
+        assert!(serde_json::from_str::<Move>("\"Q\"").is_err());
+        assert!(<CornerMap as Repr>::from_raw(0).is_err());
+        assert!(<Cube as Repr>::from_raw((0, 0)).is_err());
+    }
+}
@@ -1,18 +1,26 @@
 mod coord;
 mod cube;
 pub mod db;
+mod optimal;
+mod partial;
 mod search;
 mod solution;
 mod util;
-use crate::{CornerMap, EdgeMap, Move};
+use crate::{CornerMap, EdgeMap, MapError, Move};
 use cube::CubieCube;
 use db::CubeTableEventedBuilder;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// A two-phase Kociemba solver for the 3x3x3 Rubik's cube.
 ///
 /// Pruning tables are built automatically on the first call to
 /// [`Solver::search`]. To build them ahead of time (e.g. to show
 /// a progress indicator), use [`Solver::initialize_tables_incremental`].
+/// To search on several threads, share the tables of [`Solver::tables`]
+/// with a [`SolverWorker`] on each, or use [`Solver::solve_batch`].
 ///
 /// # Examples
 ///
@@ -24,10 +32,247 @@ use db::CubeTableEventedBuilder;
 /// let scrambled = Cube::default() * R1 * U1 * F2;
 /// let solution = solver.search(scrambled);
 /// ```
-#[derive(Default)]
 pub struct Solver {
-    db: CubeTableEventedBuilder,
+    worker: SolverWorker,
+    /// The number of steps left to build the tables of the worker.
+    remaining: i32,
+}
+
+impl Default for Solver {
+    fn default() -> Solver {
+        Solver {
+            worker: SolverWorker::new(Arc::new(SolverTables {
+                table: db::CubeTable::new_uninit(),
+            })),
+            remaining: 7,
+        }
+    }
+}
+
+/// The pruning tables of the two-phase solver, built once and shared by the
+/// [`SolverWorker`]s of several threads.
+///
+/// # Examples
+///
+/// ```no_run
+/// use cubie::{Cube, SolverTables, SolverWorker};
+/// use std::sync::Arc;
+///
+/// let tables = Arc::new(SolverTables::new());
+/// let threads: Vec<_> = (0..4)
+///     .map(|seed| {
+///         let mut worker = SolverWorker::new(tables.clone());
+///         std::thread::spawn(move || {
+///             let cube = Cube::random(&mut oorandom::Rand32::new(seed));
+///             worker.search(cube)
+///         })
+///     })
+///     .collect();
+/// for thread in threads {
+///     println!("{:?}", thread.join().unwrap());
+/// }
+/// ```
+pub struct SolverTables {
+    table: Box<db::CubeTable>,
+}
+
+impl SolverTables {
+    /// Builds the tables, which takes as long as the first search of a
+    /// [`Solver`].
+    pub fn new() -> SolverTables {
+        let mut builder = CubeTableEventedBuilder::new();
+        while builder.next() > 0 {}
+        SolverTables {
+            table: builder.table,
+        }
+    }
+
+    /// Reads tables written by [`Solver::save_tables`] or
+    /// [`SolverTables::save`], see [`Solver::load_tables`].
+    pub fn load(reader: &mut impl std::io::Read) -> std::io::Result<SolverTables> {
+        Ok(SolverTables {
+            table: db::CubeTable::read_from(reader)?,
+        })
+    }
+
+    /// Writes the tables, see [`Solver::save_tables`].
+    pub fn save(&self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        self.table.write_to(writer)
+    }
+}
+
+impl Default for SolverTables {
+    fn default() -> SolverTables {
+        SolverTables::new()
+    }
+}
+
+/// A two-phase solver using tables shared with other threads, see
+/// [`SolverTables`].
+///
+/// Each thread needs its own worker, which only holds the state of a search
+/// besides the shared tables. The searches are those of the [`Solver`] with
+/// the same name.
+pub struct SolverWorker {
+    tables: Arc<SolverTables>,
     searcher: search::Search,
+    /// The faces solutions may not turn, as a mask of [`Face`] bits.
+    ///
+    /// [`Face`]: crate::Face
+    skipped_faces: u8,
+}
+
+/// Limits on the search of [`Solver::search_with`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use cubie::{Cube, Move::*, SolverOptions};
+/// use std::time::Duration;
+///
+/// let options = SolverOptions {
+///     max_length: 20,
+///     time_budget: Some(Duration::from_millis(50)),
+///     ..SolverOptions::default()
+/// };
+/// let mut solver = cubie::Solver::default();
+/// let solution = solver.search_with(Cube::from(&[R1, U1, F2]), &options);
+/// assert!(solution.is_ok());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SolverOptions {
+    /// The maximum number of face turns of a solution, before opposite face
+    /// turns are combined into slice moves. Defaults to 21.
+    pub max_length: u32,
+    /// The number of phase 2 probes after which the search stops looking for
+    /// a shorter solution than the one it has found. Defaults to 25.
+    pub probe_min: u64,
+    /// The number of phase 2 probes after which the search gives up if it
+    /// has not found any solution. Defaults to 100,000.
+    pub probe_max: u64,
+    /// The time after which the search stops, returning the best solution
+    /// found so far if there is one. Defaults to no limit.
+    pub time_budget: Option<Duration>,
+}
+
+impl Default for SolverOptions {
+    fn default() -> SolverOptions {
+        SolverOptions {
+            max_length: 21,
+            probe_min: 25,
+            probe_max: 100_000,
+            time_budget: None,
+        }
+    }
+}
+
+/// A property shared by every state reachable from the solved cube, or
+/// from the solved cube with the faces allowed by [`Solver::set_faces`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Invariant {
+    /// The sum of the corner twists is a multiple of three.
+    CornerTwist,
+    /// The number of flipped edges is even.
+    EdgeFlip,
+    /// The permutations of the corners, edges and centers have matching
+    /// parities.
+    Parity,
+    /// The pieces between faces that may not turn are solved, and if both
+    /// faces of an axis may not turn, the edges are oriented along it.
+    AllowedFaces,
+}
+
+impl fmt::Display for Invariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Invariant::CornerTwist => "corner twist",
+            Invariant::EdgeFlip => "edge flip",
+            Invariant::Parity => "permutation parity",
+            Invariant::AllowedFaces => "pieces for the allowed faces",
+        })
+    }
+}
+
+/// The reason [`Solver::search_with`] failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveError {
+    /// The state breaks an invariant of the cube, so no sequence of moves
+    /// solves it.
+    Unsolvable(Invariant),
+    /// The state is not a valid arrangement of pieces.
+    InvalidMap(MapError),
+    /// No solution was found within the limits of the [`SolverOptions`].
+    BudgetExhausted,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Unsolvable(invariant) => write!(f, "unsolvable state: wrong {}", invariant),
+            SolveError::InvalidMap(err) => write!(f, "invalid state: {}", err),
+            SolveError::BudgetExhausted => {
+                f.write_str("no solution found within the search limits")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SolveError::InvalidMap(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Checks that a state is a valid arrangement of pieces reachable from the
+/// solved cube.
+fn check_solvable(cube: crate::Cube) -> Result<(), SolveError> {
+    let maps = [
+        cube.edges().validate(),
+        cube.corners().validate(),
+        cube.centers().validate(),
+    ];
+    for result in &maps {
+        match *result {
+            Ok(()) | Err(MapError::Orientation) => (),
+            Err(err) => return Err(SolveError::InvalidMap(err)),
+        }
+    }
+    if !cube.corners().orientation_residue().is_identity() {
+        Err(SolveError::Unsolvable(Invariant::CornerTwist))
+    } else if !cube.edges().orientation_residue().is_identity() {
+        Err(SolveError::Unsolvable(Invariant::EdgeFlip))
+    } else if !cube.has_solution() {
+        Err(SolveError::Unsolvable(Invariant::Parity))
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks the invariants of the corners or edges of a tile map whose stickers
+/// are all specified.
+fn check_partial(stickers: &crate::TileMap) -> Result<(), SolveError> {
+    use crate::tile::TileMapConversionError as TMErr;
+    let invariant = |err| match err {
+        TMErr::Corner(MapError::Orientation) => Err(SolveError::Unsolvable(Invariant::CornerTwist)),
+        TMErr::Edge(MapError::Orientation) => Err(SolveError::Unsolvable(Invariant::EdgeFlip)),
+        TMErr::Corner(err) | TMErr::Edge(err) => Err(SolveError::InvalidMap(err)),
+        // Pieces with missing or impossible stickers are left to the search.
+        _ => Ok(()),
+    };
+    match (stickers.corners(), stickers.edges()) {
+        (Ok(corners), Ok(edges)) => check_solvable(crate::Cube::new(
+            crate::CenterMap::default(),
+            corners,
+            edges,
+        )),
+        (corners, edges) => {
+            corners.map(drop).or_else(invariant)?;
+            edges.map(drop).or_else(invariant)
+        }
+    }
 }
 
 fn conv_e(set: EdgeMap) -> cube::CubieEdges {
@@ -73,6 +318,22 @@ fn conv(set: CornerMap) -> cube::CubieCorners {
 }
 
 use crate::FaceMove;
+
+/// The face moves in the solver's numbering.
+const FACE_MOVES: [FaceMove; 18] = {
+    use crate::FaceMove::*;
+    [
+        U1, U2, U3, R1, R2, R3, F1, F2, F3, D1, D2, D3, L1, L2, L3, B1, B2, B3,
+    ]
+};
+
+fn cubie_cube(cube: crate::FixedCentersCube) -> CubieCube {
+    CubieCube {
+        edges: conv_e(cube.edges()),
+        corners: conv(cube.corners()),
+    }
+}
+
 fn replace_with_slice(mut turns: impl Iterator<Item = FaceMove>) -> Vec<Move> {
     use crate::moves::{MoveAngle::*, MoveKind};
     let mut out = Vec::<Move>::default();
@@ -123,7 +384,168 @@ impl Solver {
     /// }
     /// ```
     pub fn initialize_tables_incremental(&mut self) -> i32 {
-        self.db.next()
+        if self.remaining <= 0 {
+            return 0;
+        }
+        let tables = Arc::get_mut(&mut self.worker.tables).expect("tables are shared once built");
+        tables.table.init_step(self.remaining);
+        self.remaining -= 1;
+        self.remaining
+    }
+
+    /// Writes the pruning tables, building them first if needed, so that
+    /// [`Solver::load_tables`] can skip building them in another process.
+    ///
+    /// The tables are written as they are in memory, in the byte order of
+    /// the machine, after a header with the version of the crate and a
+    /// checksum.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use std::io::BufWriter;
+    ///
+    /// let mut solver = cubie::Solver::default();
+    /// let mut file = BufWriter::new(File::create("tables.bin")?);
+    /// solver.save_tables(&mut file)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn save_tables(&mut self, writer: &mut impl std::io::Write) -> std::io::Result<()> {
+        self.ensure_initialized();
+        self.worker.tables.save(writer)
+    }
+
+    /// Replaces the pruning tables with ones written by
+    /// [`Solver::save_tables`], instead of building them.
+    ///
+    /// Fails with [`std::io::ErrorKind::InvalidData`] if the tables were
+    /// written by another version of the crate or on a machine with another
+    /// byte order, or if they are corrupted. Every value the solver indexes
+    /// its tables with is checked to be in range, so data from an untrusted
+    /// source can be loaded too. The tables of the solver are left as they
+    /// were on failure.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use std::io::BufReader;
+    ///
+    /// let mut solver = cubie::Solver::default();
+    /// if let Ok(file) = File::open("tables.bin") {
+    ///     // Stale or corrupted tables are rebuilt on the first search.
+    ///     let _ = solver.load_tables(&mut BufReader::new(file));
+    /// }
+    /// ```
+    pub fn load_tables(&mut self, reader: &mut impl std::io::Read) -> std::io::Result<()> {
+        self.worker.tables = Arc::new(SolverTables::load(reader)?);
+        self.remaining = 0;
+        Ok(())
+    }
+
+    /// Returns the pruning tables, building them first if needed, to share
+    /// them with [`SolverWorker`]s on other threads.
+    pub fn tables(&mut self) -> Arc<SolverTables> {
+        self.ensure_initialized();
+        self.worker.tables.clone()
+    }
+
+    /// Solves each of `cubes` as [`Solver::search`] does, spreading the work
+    /// over as many threads as the machine runs in parallel. The solutions
+    /// are in the order of `cubes`.
+    ///
+    /// The threads share the tables of the solver, and use the faces allowed
+    /// by [`Solver::set_faces`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the states is invalid or unsolvable, or if no
+    /// solution is found within the default limits.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use cubie::Cube;
+    ///
+    /// let mut rng = oorandom::Rand32::new(25);
+    /// let cubes: Vec<_> = (0..1000).map(|_| Cube::random(&mut rng)).collect();
+    /// let mut solver = cubie::Solver::default();
+    /// let solutions = solver.solve_batch(&cubes);
+    /// let total: usize = solutions.iter().map(Vec::len).sum();
+    /// println!("{} moves on average", total as f64 / cubes.len() as f64);
+    /// ```
+    pub fn solve_batch(&mut self, cubes: &[crate::Cube]) -> Vec<Vec<Move>> {
+        let tables = self.tables();
+        let threads = std::thread::available_parallelism()
+            .map_or(1, |threads| threads.get())
+            .min(cubes.len());
+        // Solving times vary a lot, so each thread takes the next cube left.
+        let next = &AtomicUsize::new(0);
+        let mut solutions = vec![Vec::new(); cubes.len()];
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    let mut worker = SolverWorker::new(tables.clone());
+                    worker.skipped_faces = self.worker.skipped_faces;
+                    scope.spawn(move || {
+                        let mut solved = Vec::new();
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            match cubes.get(i) {
+                                Some(&cube) => solved.push((i, worker.search(cube))),
+                                None => return solved,
+                            }
+                        }
+                    })
+                })
+                .collect();
+            for worker in workers {
+                let solved = worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+                for (i, solution) in solved {
+                    solutions[i] = solution;
+                }
+            }
+        });
+        solutions
+    }
+
+    /// Restricts the solutions to turns of the given faces, in the
+    /// orientation the cube is held in. For example, a robot without a
+    /// motor on the back face uses `[Up, Down, Front, Right, Left]`.
+    ///
+    /// Every search of the solver is restricted, including
+    /// [`Solver::search_optimal`], and solutions are made only of outer face
+    /// turns. The pruning tables are shared with the unrestricted search,
+    /// since a distance using all the moves is a lower bound on the distance
+    /// using some of them, so nothing is rebuilt.
+    ///
+    /// Without some faces, not every state can be solved: the pieces between
+    /// faces that may not turn must already be solved, and if both faces of
+    /// an axis may not turn, the edges must be oriented along it. Searches
+    /// fail with [`Invariant::AllowedFaces`] otherwise. Solutions also get
+    /// longer, so a `max_length` of 23 or more keeps the search fast with a
+    /// face missing.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use cubie::{Cube, Face::*, SolverOptions};
+    ///
+    /// let mut solver = cubie::Solver::default();
+    /// solver.set_faces(&[Up, Down, Front, Right, Left]);
+    /// let cube = Cube::random(&mut oorandom::Rand32::new(5));
+    /// let options = SolverOptions {
+    ///     max_length: 23,
+    ///     ..SolverOptions::default()
+    /// };
+    /// let solution = solver.search_with(cube, &options).unwrap();
+    /// assert!(solution.iter().all(|mv| mv.face() != Back));
+    /// ```
+    pub fn set_faces(&mut self, faces: &[crate::Face]) {
+        self.worker.set_faces(faces);
     }
 
     /// Finds a solution for the given cube state.
@@ -139,32 +561,851 @@ impl Solver {
     ///
     /// # Panics
     ///
-    /// Panics if no solution is found within the internal probe limit.
+    /// Panics if the state is invalid or unsolvable, or if no solution is
+    /// found within the default limits. Use [`Solver::search_with`] to handle
+    /// these cases.
     ///
     /// [`Move`]: crate::Move
     pub fn search(&mut self, cube: crate::Cube) -> Vec<crate::Move> {
-        let cubex: crate::FixedCentersCube = cube.into();
-        let cc = CubieCube {
-            edges: conv_e(cubex.edges()),
-            corners: conv(cubex.corners()),
+        match self.search_with(cube, &SolverOptions::default()) {
+            Ok(solution) => solution,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Finds a solution for the given cube state within the limits of
+    /// `options`, see [`Solver::search`].
+    ///
+    /// Fails without searching if the state is not a valid arrangement of
+    /// pieces or cannot be solved.
+    pub fn search_with(
+        &mut self,
+        cube: crate::Cube,
+        options: &SolverOptions,
+    ) -> Result<Vec<crate::Move>, SolveError> {
+        self.worker.check_solvable(cube)?;
+        self.ensure_initialized();
+        self.worker.search_with(cube, options)
+    }
+
+    /// Finds a shortest solution for a cube state close to solved, proving
+    /// that no shorter one exists in the half turn metric.
+    ///
+    /// The search is an IDA* bounded by the phase 1 pruning tables along all
+    /// three axes. No larger table is built for it, so unlike
+    /// [`Solver::search`] its running time grows quickly with the length of
+    /// the solution: solutions of up to about 13 moves take seconds, each
+    /// further move multiplies that by about ten. It suits patterns and the
+    /// parts of a fewest moves solution, while a random state, needing about
+    /// 18 moves, takes days. Set `options.time_budget` to give up on states
+    /// that are too far, or use [`Solver::solutions`] to get good solutions
+    /// on the way. After each depth is searched without finding a solution,
+    /// `progress` is called with that depth.
+    ///
+    /// The solution is made only of outer face turns. With the faces
+    /// restricted by [`Solver::set_faces`], the bounds still come from the
+    /// unrestricted pruning tables, so they are looser and the search is
+    /// slower, and the shortest solution may be longer than 20 moves.
+    ///
+    /// Only the `max_length` and `time_budget` of `options` are used.
+    ///
+    /// # Errors
+    ///
+    /// Fails without searching if the state is not a valid arrangement of
+    /// pieces or cannot be solved, and with [`SolveError::BudgetExhausted`]
+    /// if there is no solution of at most `max_length` moves or the time
+    /// budget runs out.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use cubie::{Cube, Move::*, SolverOptions};
+    /// use std::time::Duration;
+    ///
+    /// let mut solver = cubie::Solver::default();
+    /// let checkerboard = Cube::from(&[U2, D2, F2, B2, R2, L2]);
+    /// let options = SolverOptions {
+    ///     time_budget: Some(Duration::from_secs(60)),
+    ///     ..SolverOptions::default()
+    /// };
+    /// let solution = solver
+    ///     .search_optimal(checkerboard, &options, |depth| {
+    ///         println!("no solution of {} moves", depth);
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(solution.len(), 6);
+    /// ```
+    pub fn search_optimal(
+        &mut self,
+        cube: crate::Cube,
+        options: &SolverOptions,
+        mut progress: impl FnMut(u32),
+    ) -> Result<Vec<crate::Move>, SolveError> {
+        self.worker.check_solvable(cube)?;
+        let rotation = cube.centers();
+        self.ensure_initialized();
+        let mut search = optimal::OptimalSearch::new(&self.worker.tables.table);
+        search.deadline = options.time_budget.map(|budget| Instant::now() + budget);
+        search.skipped_moves = self.worker.skipped_moves(rotation);
+        let solution = search
+            .solve(cubie_cube(cube.into()), options.max_length, &mut progress)
+            .ok_or(SolveError::BudgetExhausted)?;
+        Ok(solution
+            .iter()
+            .map(|&mv| crate::Move::from(FACE_MOVES[mv as usize]).projection(rotation))
+            .collect())
+    }
+
+    /// Finds a shortest sequence of face turns bringing every specified
+    /// sticker of `tiles` home, whatever becomes of the `None` tiles.
+    ///
+    /// A sticker is home on the face whose center has its color, so the
+    /// stickers of the first two layers alone ask to finish F2L, and the up
+    /// colored stickers of the last layer alone ask to orient it. Centers
+    /// that are `None` are taken to be where they are on a cube held in the
+    /// standard orientation. The faces allowed by [`Solver::set_faces`] are
+    /// the only ones turned, and the pruning tables are not needed.
+    ///
+    /// The search is an IDA* like [`Solver::search_optimal`], bounded by how
+    /// far each pair of pieces with a specified sticker is from home. Goals
+    /// of a step of a solving method take milliseconds to seconds, while
+    /// goals needing more than about 12 moves take much longer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the specified stickers cannot all be home at once. Use
+    /// [`Solver::search_partial_with`] to handle this case.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use cubie::{Cube, Face, Move::*, TileMap};
+    ///
+    /// let cube = Cube::from(&[R1, U1, R3, U1, F2]);
+    /// let mut tiles = TileMap::from(cube);
+    /// // Only care about the stickers of the down layer.
+    /// for (i, tile) in tiles.as_array_mut().iter_mut().enumerate() {
+    ///     if *tile != Some(Face::Down) && i % 9 != 4 {
+    ///         *tile = None;
+    ///     }
+    /// }
+    /// let mut solver = cubie::Solver::default();
+    /// let solution = solver.search_partial(&tiles);
+    /// assert!(solution.len() <= 5);
+    /// ```
+    pub fn search_partial(&mut self, tiles: &crate::TileMap) -> Vec<crate::Move> {
+        match self.search_partial_with(tiles, &SolverOptions::default()) {
+            Ok(solution) => solution,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Finds a shortest sequence of face turns bringing every specified
+    /// sticker of `tiles` home within the `max_length` and `time_budget` of
+    /// `options`, see [`Solver::search_partial`].
+    ///
+    /// Fails without searching if the specified stickers cannot all be home
+    /// at once. The invariants of the corners or edges are only checked when
+    /// all of their stickers are specified, otherwise the search runs until
+    /// the limits of `options`.
+    pub fn search_partial_with(
+        &mut self,
+        tiles: &crate::TileMap,
+        options: &SolverOptions,
+    ) -> Result<Vec<crate::Move>, SolveError> {
+        use crate::tile::TileMapConversionError as TMErr;
+        use crate::Face;
+        let mut centers = tiles.clone();
+        for face in Face::faces() {
+            centers.as_array_mut()[face as usize * 9 + 4].get_or_insert(face);
+        }
+        match centers.centers() {
+            Ok(_) => (),
+            Err(TMErr::Center(err)) => return Err(SolveError::InvalidMap(err)),
+            Err(_) => unreachable!("every center is set"),
+        }
+        let home = |color| {
+            Face::faces()
+                .find(|&face| centers.as_array()[face as usize * 9 + 4] == Some(color))
+                .unwrap()
         };
-        if self.db.remaining > 0 {
+        let mut stickers = crate::TileMap::default();
+        for (sticker, tile) in stickers.as_array_mut().iter_mut().zip(tiles.as_array()) {
+            *sticker = tile.map(home);
+        }
+        stickers.store_identity_centers();
+        check_partial(&stickers)?;
+
+        let mut search =
+            partial::PartialSearch::new(stickers.as_array()).map_err(SolveError::InvalidMap)?;
+        search.deadline = options.time_budget.map(|budget| Instant::now() + budget);
+        search.skipped_moves = self.worker.skipped_moves(crate::CenterMap::default());
+        let solution = search
+            .solve(options.max_length)
+            .ok_or(SolveError::BudgetExhausted)?;
+        Ok(solution
+            .iter()
+            .map(|&mv| Move::from(FACE_MOVES[mv as usize]))
+            .collect())
+    }
+
+    /// Returns an iterator over solutions of the given cube state, each
+    /// strictly shorter than the one before.
+    ///
+    /// The first solution comes from the two-phase search as soon as it is
+    /// found, without looking for a shorter one as [`Solver::search`] does.
+    /// Each following call resumes the search where it left off, until it
+    /// gives up after `options.probe_max` probes without improvement. Then
+    /// the IDA* of [`Solver::search_optimal`] looks for a yet shorter
+    /// solution, which is optimal, and the iterator ends once it is found or
+    /// proven not to exist, see [`Solutions::is_optimal`].
+    ///
+    /// The proof can take hours for a random state, so callers wanting a
+    /// result now stop calling [`Iterator::next`] when the last solution is
+    /// good enough, or bound the whole iteration with
+    /// `options.time_budget`. Only the `max_length`, `probe_max` and
+    /// `time_budget` of `options` are used.
+    ///
+    /// Solutions are made only of outer face turns, so their lengths are
+    /// counted in the half turn metric.
+    ///
+    /// Fails without searching if the state is not a valid arrangement of
+    /// pieces or cannot be solved.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use cubie::{Cube, SolverOptions};
+    /// use std::time::Duration;
+    ///
+    /// let mut solver = cubie::Solver::default();
+    /// let cube = Cube::random(&mut oorandom::Rand32::new(7));
+    /// let options = SolverOptions {
+    ///     time_budget: Some(Duration::from_secs(1)),
+    ///     ..SolverOptions::default()
+    /// };
+    /// for solution in solver.solutions(cube, &options).unwrap() {
+    ///     println!("found {} moves", solution.len());
+    /// }
+    /// ```
+    pub fn solutions(
+        &mut self,
+        cube: crate::Cube,
+        options: &SolverOptions,
+    ) -> Result<Solutions<'_>, SolveError> {
+        self.worker.check_solvable(cube)?;
+        self.ensure_initialized();
+        let deadline = options.time_budget.map(|budget| Instant::now() + budget);
+        self.worker.searcher.deadline = deadline;
+        self.worker.searcher.skipped_moves = self.worker.skipped_moves(cube.centers());
+        Ok(Solutions {
+            solver: self,
+            cube,
+            options: *options,
+            deadline,
+            length: None,
+            stage: Stage::Start,
+            optimal: false,
+        })
+    }
+
+    fn ensure_initialized(&mut self) {
+        if self.remaining > 0 {
             //insure initializied
-            while self.db.next() > 0 {}
-        }
-        let solve_turns = self
-            .searcher
-            .solve_cc(&self.db.table, cc, 21, 100000, 25, 0);
-        use crate::FaceMove::*;
-        let map = [
-            U1, U2, U3, R1, R2, R3, F1, F2, F3, D1, D2, D3, L1, L2, L3, B1, B2, B3,
-        ];
+            while self.initialize_tables_incremental() > 0 {}
+        }
+    }
+
+    /// Finds a solution made only of outer face turns for a cube held in the
+    /// standard orientation.
+    pub(crate) fn search_face_moves(&mut self, cube: crate::FixedCentersCube) -> Vec<FaceMove> {
+        self.ensure_initialized();
+        self.worker
+            .search_face_moves_with(cube, crate::CenterMap::default(), &SolverOptions::default())
+            .unwrap()
+    }
+}
+
+impl SolverWorker {
+    /// Creates a worker using `tables`.
+    pub fn new(tables: Arc<SolverTables>) -> SolverWorker {
+        SolverWorker {
+            tables,
+            searcher: search::Search::default(),
+            skipped_faces: 0,
+        }
+    }
+
+    /// Restricts the solutions to turns of the given faces, see
+    /// [`Solver::set_faces`].
+    pub fn set_faces(&mut self, faces: &[crate::Face]) {
+        self.skipped_faces = crate::Face::faces()
+            .filter(|face| !faces.contains(face))
+            .fold(0, |mask, face| mask | 1 << face as u8);
+    }
+
+    /// Checks that a state can be solved, turning only the allowed faces.
+    fn check_solvable(&self, cube: crate::Cube) -> Result<(), SolveError> {
+        use crate::{Corner, CornerOrientation, Edge, EdgeOrientation, Face};
+        check_solvable(cube)?;
+        let skipped = self.skipped_moves(cube.centers());
+        let forbidden = |face: Face| {
+            FACE_MOVES
+                .iter()
+                .enumerate()
+                .any(|(i, mv)| mv.face() == face && (skipped >> i) & 1 != 0)
+        };
+        let state = crate::FixedCentersCube::from(cube);
+        let edges = Edge::edges()
+            .filter(|edge| forbidden(edge.faces().0) && forbidden(edge.faces().1))
+            .all(|edge| state.edges().get(edge) == (edge, EdgeOrientation::Identity));
+        let corners = Corner::corners()
+            .filter(|corner| {
+                [corner.x(), corner.y(), corner.z()]
+                    .iter()
+                    .all(|&f| forbidden(f))
+            })
+            .all(|corner| state.corners().get(corner) == (corner, CornerOrientation::Identity));
+        // Only quarter turns of the front and back faces flip edges.
+        let oriented = Face::faces()
+            .filter(|&face| forbidden(face) && forbidden(face.opposite()))
+            .all(|axis| {
+                let sym = crate::Symmetry::rotations()
+                    .find(|sym| sym.face(axis) == Face::Front)
+                    .unwrap();
+                state
+                    .conjugate(sym)
+                    .edges()
+                    .iter()
+                    .all(|(_, (_, flip))| flip == EdgeOrientation::Identity)
+            });
+        if edges && corners && oriented {
+            Ok(())
+        } else {
+            Err(SolveError::Unsolvable(Invariant::AllowedFaces))
+        }
+    }
+
+    /// The moves solutions may not use, as a mask over the solver's
+    /// numbering, for a cube whose centers are rotated by `rotation`.
+    fn skipped_moves(&self, rotation: crate::CenterMap) -> u32 {
+        FACE_MOVES
+            .iter()
+            .enumerate()
+            .filter(|(_, mv)| self.skipped_faces & 1 << mv.projection(rotation).face() as u8 != 0)
+            .fold(0, |mask, (i, _)| mask | 1 << i)
+    }
+
+    /// Finds a solution for the given cube state, see [`Solver::search`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state is invalid or unsolvable, or if no solution is
+    /// found within the default limits.
+    pub fn search(&mut self, cube: crate::Cube) -> Vec<crate::Move> {
+        match self.search_with(cube, &SolverOptions::default()) {
+            Ok(solution) => solution,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Finds a solution for the given cube state within the limits of
+    /// `options`, see [`Solver::search_with`].
+    pub fn search_with(
+        &mut self,
+        cube: crate::Cube,
+        options: &SolverOptions,
+    ) -> Result<Vec<crate::Move>, SolveError> {
+        self.check_solvable(cube)?;
         let rotation = cube.centers();
-        replace_with_slice(
+        let solution = self
+            .search_face_moves_with(cube.into(), rotation, options)
+            .ok_or(SolveError::BudgetExhausted)?;
+        let turns = solution.into_iter().map(|mv| mv.projection(rotation));
+        // Slice moves would turn the centers, renaming the faces after them.
+        if self.skipped_faces != 0 {
+            Ok(turns.map(Move::from).collect())
+        } else {
+            Ok(replace_with_slice(turns))
+        }
+    }
+
+    /// Finds a solution of a cube with fixed centers, whose centers were
+    /// rotated by `rotation` before fixing them.
+    fn search_face_moves_with(
+        &mut self,
+        cube: crate::FixedCentersCube,
+        rotation: crate::CenterMap,
+        options: &SolverOptions,
+    ) -> Option<Vec<FaceMove>> {
+        // The two-phase search cannot handle an empty solution.
+        if cube == crate::FixedCentersCube::default() {
+            return Some(Vec::new());
+        }
+        let cc = cubie_cube(cube);
+        self.searcher.deadline = options.time_budget.map(|budget| Instant::now() + budget);
+        self.searcher.skipped_moves = self.skipped_moves(rotation);
+        let solve_turns = self.searcher.solve_cc(
+            &self.tables.table,
+            cc,
+            options.max_length,
+            options.probe_max,
+            options.probe_min,
+            0,
+        )?;
+        Some(
             solve_turns
-                .unwrap()
                 .iter()
-                .map(|mv| map[*mv as usize].projection(rotation)),
+                .map(|mv| FACE_MOVES[*mv as usize])
+                .collect(),
         )
     }
 }
+
+/// The search a [`Solutions`] iterator runs on its next call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stage {
+    /// A new two-phase search.
+    Start,
+    /// The two-phase search resumed for a shorter solution.
+    Improve,
+    /// The IDA* search for a shorter, optimal solution.
+    Prove,
+    Done,
+}
+
+/// An iterator over successively shorter solutions of a cube, see
+/// [`Solver::solutions`].
+pub struct Solutions<'a> {
+    solver: &'a mut Solver,
+    cube: crate::Cube,
+    options: SolverOptions,
+    deadline: Option<Instant>,
+    /// The number of moves of the last solution.
+    length: Option<u32>,
+    stage: Stage,
+    optimal: bool,
+}
+
+impl Solutions<'_> {
+    /// Whether the last solution yielded is proven to be a shortest one. Once
+    /// it is, the iterator ends.
+    pub fn is_optimal(&self) -> bool {
+        self.optimal
+    }
+
+    fn expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    fn turns(&self, solution: Vec<u8>) -> Vec<Move> {
+        let rotation = self.cube.centers();
+        solution
+            .iter()
+            .map(|&mv| Move::from(FACE_MOVES[mv as usize]).projection(rotation))
+            .collect()
+    }
+}
+
+impl Iterator for Solutions<'_> {
+    type Item = Vec<Move>;
+
+    fn next(&mut self) -> Option<Vec<Move>> {
+        let solution = match self.stage {
+            Stage::Done => return None,
+            _ if self.expired() => None,
+            Stage::Start => {
+                let cube = crate::FixedCentersCube::from(self.cube);
+                // The two-phase search cannot handle an empty solution.
+                if cube == crate::FixedCentersCube::default() {
+                    self.optimal = true;
+                    Some(Vec::new())
+                } else {
+                    self.stage = Stage::Improve;
+                    self.solver.worker.searcher.solve_cc(
+                        &self.solver.worker.tables.table,
+                        cubie_cube(cube),
+                        self.options.max_length,
+                        self.options.probe_max,
+                        0,
+                        0,
+                    )
+                }
+            }
+            Stage::Improve => self.solver.worker.searcher.next(
+                &self.solver.worker.tables.table,
+                self.options.probe_max,
+                0,
+            ),
+            Stage::Prove => {
+                let max_depth = self.length.map_or(self.options.max_length, |len| len - 1);
+                let mut search = optimal::OptimalSearch::new(&self.solver.worker.tables.table);
+                search.deadline = self.deadline;
+                search.skipped_moves = self.solver.worker.searcher.skipped_moves;
+                let solution = search.solve(cubie_cube(self.cube.into()), max_depth, &mut |_| ());
+                // Without a previous solution, finding none is not a proof.
+                self.optimal = solution.is_some() || (self.length.is_some() && !search.expired());
+                solution
+            }
+        };
+        match solution {
+            Some(solution) if !self.optimal => {
+                self.length = Some(solution.len() as u32);
+                Some(self.turns(solution))
+            }
+            Some(solution) => {
+                self.stage = Stage::Done;
+                Some(self.turns(solution))
+            }
+            None if self.stage == Stage::Improve && !self.expired() => {
+                self.stage = Stage::Prove;
+                self.next()
+            }
+            None => {
+                self.stage = Stage::Done;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cube, Move::*};
+
+    #[test]
+    fn optimal_solutions() {
+        let mut solver = Solver::default();
+        let checkerboard = Cube::from(&[U2, D2, F2, B2, R2, L2]) * X1;
+        let options = SolverOptions::default();
+        let mut depths = Vec::new();
+        let solution = solver
+            .search_optimal(checkerboard, &options, |depth| depths.push(depth))
+            .unwrap();
+        assert_eq!(solution.len(), 6);
+        assert!((checkerboard * Cube::from(&solution)).is_solved());
+        assert_eq!(depths.last(), Some(&5));
+
+        let mut rng = oorandom::Rand32::new(19);
+        for _ in 0..4 {
+            let scramble: Vec<Move> = (0..7)
+                .map(|_| FACE_MOVES[rng.rand_range(0..18) as usize].into())
+                .collect();
+            let cube = Cube::from(&scramble);
+            let solution = solver.search_optimal(cube, &options, |_| ()).unwrap();
+            assert!(solution.len() <= scramble.len());
+            assert!((cube * Cube::from(&solution)).is_solved());
+        }
+        assert_eq!(
+            solver.search_optimal(Cube::default(), &options, |_| ()),
+            Ok(Vec::new())
+        );
+
+        let short = SolverOptions {
+            max_length: 5,
+            ..options
+        };
+        assert_eq!(
+            solver.search_optimal(checkerboard, &short, |_| ()),
+            Err(SolveError::BudgetExhausted)
+        );
+    }
+
+    #[test]
+    fn shorter_solutions() {
+        let mut solver = Solver::default();
+        let options = SolverOptions::default();
+        let solved: Vec<_> = solver
+            .solutions(Cube::default(), &options)
+            .unwrap()
+            .collect();
+        assert_eq!(solved, vec![Vec::new()]);
+
+        let mut rng = oorandom::Rand32::new(21);
+        let scramble: Vec<Move> = (0..8)
+            .map(|_| FACE_MOVES[rng.rand_range(0..18) as usize].into())
+            .collect();
+        let cube = Cube::from(&scramble) * Y1;
+        let mut solutions = solver.solutions(cube, &options).unwrap();
+        let mut lengths = Vec::new();
+        for solution in &mut solutions {
+            assert!((cube * Cube::from(&solution)).is_solved());
+            lengths.push(solution.len());
+        }
+        assert!(solutions.is_optimal());
+        assert!(lengths.windows(2).all(|pair| pair[0] > pair[1]));
+        let optimal = solver.search_optimal(cube, &options, |_| ()).unwrap().len();
+        assert_eq!(lengths.last(), Some(&optimal));
+
+        let options = SolverOptions {
+            time_budget: Some(Duration::from_millis(300)),
+            ..options
+        };
+        let cube = Cube::random(&mut rng);
+        let mut solutions = solver.solutions(cube, &options).unwrap();
+        let mut last = usize::MAX;
+        for solution in &mut solutions {
+            assert!(solution.len() < last);
+            assert!((cube * Cube::from(&solution)).is_solved());
+            last = solution.len();
+        }
+        assert!(!solutions.is_optimal());
+    }
+
+    #[test]
+    fn restricted_faces() {
+        use crate::Face::*;
+        let mut solver = Solver::default();
+        let options = SolverOptions {
+            max_length: 30,
+            ..SolverOptions::default()
+        };
+        let mut rng = oorandom::Rand32::new(22);
+        let face_sets: [&[crate::Face]; 3] = [
+            &[Up, Down, Front, Right, Left],
+            &[Up, Front, Right, Left],
+            &[Front, Right, Back, Left],
+        ];
+        for &faces in &face_sets {
+            solver.set_faces(faces);
+            let moves: Vec<Move> = FaceMove::moves()
+                .filter(|mv| faces.contains(&mv.face()))
+                .map(Move::from)
+                .collect();
+            let cube = (0..30).fold(Cube::from(&[Y1]), |cube, _| {
+                cube * moves[rng.rand_range(0..moves.len() as u32) as usize]
+            });
+            let solution = solver.search_with(cube, &options).unwrap();
+            assert!((cube * Cube::from(&solution)).is_solved());
+            assert!(solution.iter().all(|mv| faces.contains(&mv.face())));
+        }
+
+        let unsolvable = Err(SolveError::Unsolvable(Invariant::AllowedFaces));
+        assert_eq!(solver.search_with(Cube::from(&[U1]), &options), unsolvable);
+        solver.set_faces(&[Up, Front, Right, Left]);
+        assert_eq!(
+            solver.search_with(Cube::from(&[Y1, B1]), &options),
+            unsolvable
+        );
+        assert!(solver.search_with(Cube::from(&[B1, Y1]), &options).is_ok());
+
+        solver.set_faces(&[Right, Up]);
+        let cube = Cube::from(&[R1, U1, R3, U2]);
+        let solution = solver.search_optimal(cube, &options, |_| ());
+        assert_eq!(solution, Ok(vec![U2, R1, U3, R3]));
+        assert_eq!(
+            solver.search_optimal(Cube::from(&[F1]), &options, |_| ()),
+            unsolvable
+        );
+    }
+
+    #[test]
+    fn partial_solutions() {
+        use crate::tile::{tiles_from_corner, tiles_from_edge};
+        use crate::{Corner, Edge, Face, TileMap};
+        let pieces: Vec<Vec<usize>> = Corner::corners()
+            .map(|corner| tiles_from_corner(corner).to_vec())
+            .chain(Edge::edges().map(|edge| {
+                let (a, b) = tiles_from_edge(edge);
+                vec![a, b]
+            }))
+            .collect();
+        // The stickers of `cube` which `keep` accepts, by the colors of their
+        // piece and their own.
+        let stickers = |cube: Cube, keep: &dyn Fn(&[Face], Face) -> bool| {
+            let full = TileMap::from(cube);
+            let mut tiles = TileMap::default();
+            for face in Face::faces() {
+                tiles.as_array_mut()[face as usize * 9 + 4] =
+                    full.as_array()[face as usize * 9 + 4];
+            }
+            for piece in &pieces {
+                let colors: Vec<_> = piece.iter().map(|&t| full.as_array()[t].unwrap()).collect();
+                for (&tile, &color) in piece.iter().zip(&colors) {
+                    if keep(&colors, color) {
+                        tiles.as_array_mut()[tile] = Some(color);
+                    }
+                }
+            }
+            tiles
+        };
+        let home = |cube: Cube, keep: &dyn Fn(&[Face], Face) -> bool| {
+            let tiles = stickers(cube, keep);
+            let tiles = tiles.as_array();
+            (0..54).all(|tile| tiles[tile].is_none() || tiles[tile] == tiles[tile / 9 * 9 + 4])
+        };
+        let f2l = |colors: &[Face], _| !colors.contains(&Face::Up);
+        let oll = |colors: &[Face], color| f2l(colors, color) || color == Face::Up;
+
+        let mut solver = Solver::default();
+        let cube = Cube::from(&[R1, U1, R3, U3, F3, U2, F1]);
+        let solution = solver.search_partial(&stickers(cube, &f2l));
+        assert!(solution.len() <= 7);
+        assert!(home(cube * Cube::from(&solution), &f2l));
+
+        let sune = Cube::from(&[R1, U1, R3, U1, R1, U2, R3]);
+        let cube = sune * Y1;
+        let solution = solver.search_partial(&stickers(cube, &oll));
+        assert!(solution.len() <= 7);
+        assert!(home(cube * Cube::from(&solution), &oll));
+
+        let tiles = stickers(Cube::default(), &|_, color| color == Face::Up);
+        assert!(solver.search_partial(&tiles).is_empty());
+
+        let options = SolverOptions::default();
+        let (a, b) = tiles_from_edge(Edge::FR);
+        let (c, d) = tiles_from_edge(Edge::BR);
+        let mut impossible = stickers(Cube::default(), &f2l);
+        impossible.as_array_mut()[b] = impossible.as_array()[a];
+        assert_eq!(
+            solver.search_partial_with(&impossible, &options),
+            Err(SolveError::InvalidMap(MapError::OutOfBounds))
+        );
+        let mut twice = stickers(Cube::default(), &f2l);
+        twice.as_array_mut()[c] = twice.as_array()[a];
+        twice.as_array_mut()[d] = twice.as_array()[b];
+        assert_eq!(
+            solver.search_partial_with(&twice, &options),
+            Err(SolveError::InvalidMap(MapError::Duplicate))
+        );
+        let mut flipped = stickers(Cube::default(), &|_, _| true);
+        flipped.as_array_mut().swap(a, b);
+        assert_eq!(
+            solver.search_partial_with(&flipped, &options),
+            Err(SolveError::Unsolvable(Invariant::EdgeFlip))
+        );
+    }
+
+    #[test]
+    fn saved_tables() {
+        let mut solver = Solver::default();
+        let mut bytes = Vec::new();
+        solver.save_tables(&mut bytes).unwrap();
+        let mut loaded = Solver::default();
+        loaded.load_tables(&mut bytes.as_slice()).unwrap();
+        assert_eq!(loaded.initialize_tables_incremental(), 0);
+        let cube = Cube::random(&mut oorandom::Rand32::new(24));
+        assert!((cube * Cube::from(&loaded.search(cube))).is_solved());
+
+        let kind = |bytes: &[u8]| {
+            let mut solver = Solver::default();
+            let err = solver.load_tables(&mut &bytes[..]).unwrap_err();
+            assert_eq!(solver.initialize_tables_incremental(), 6);
+            err.kind()
+        };
+        let mut corrupted = bytes.clone();
+        corrupted[bytes.len() / 2] ^= 1;
+        assert_eq!(kind(&corrupted), std::io::ErrorKind::InvalidData);
+        let mut stale = bytes.clone();
+        stale[8] ^= 1;
+        assert_eq!(kind(&stale), std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            kind(&bytes[..bytes.len() - 1]),
+            std::io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(kind(b"not tables"), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(kind(&[0; 64]), std::io::ErrorKind::InvalidData);
+
+        // A valid checksum does not make out of range indices acceptable.
+        let mut crafted = db::CubeTable::read_from(&mut bytes.as_slice()).unwrap();
+        crafted.flip_s2rf[0] = db::N_FLIP as u16;
+        let mut out_of_range = Vec::new();
+        crafted.write_to(&mut out_of_range).unwrap();
+        assert_eq!(kind(&out_of_range), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn batch_solutions() {
+        let mut rng = oorandom::Rand32::new(25);
+        let cubes: Vec<_> = (0..12).map(|_| Cube::random(&mut rng)).collect();
+        let mut solver = Solver::default();
+        let solutions = solver.solve_batch(&cubes);
+        assert_eq!(solutions.len(), cubes.len());
+        for (&cube, solution) in cubes.iter().zip(&solutions) {
+            assert!((cube * Cube::from(solution)).is_solved());
+        }
+        assert!(solver.solve_batch(&[]).is_empty());
+
+        let tables = solver.tables();
+        let threads: Vec<_> = cubes
+            .chunks(4)
+            .map(|chunk| {
+                let mut worker = SolverWorker::new(tables.clone());
+                worker.set_faces(&[crate::Face::Up, crate::Face::Front, crate::Face::Right]);
+                let chunk = chunk.to_vec();
+                std::thread::spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|&cube| worker.search_with(cube, &SolverOptions::default()))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for thread in threads {
+            for result in thread.join().unwrap() {
+                assert_eq!(result, Err(SolveError::Unsolvable(Invariant::AllowedFaces)));
+            }
+        }
+    }
+
+    #[test]
+    fn fallible_search() {
+        use crate::{CenterMap, Edge, EdgeOrientation};
+        let mut solver = Solver::default();
+        let options = SolverOptions::default();
+        let swap = |a: Edge, b: Edge| {
+            EdgeMap::default()
+                .iter()
+                .map(move |(edge, (position, flip))| {
+                    let position = match position {
+                        p if p == a => b,
+                        p if p == b => a,
+                        p => p,
+                    };
+                    (edge, (position, flip))
+                })
+        };
+        let with_edges = |edges| Cube::new(CenterMap::default(), CornerMap::default(), edges);
+
+        let swapped = EdgeMap::from_iter(swap(Edge::FU, Edge::RU)).unwrap();
+        assert_eq!(
+            solver.search_with(with_edges(swapped), &options),
+            Err(SolveError::Unsolvable(Invariant::Parity))
+        );
+        let flipped = unsafe { EdgeMap::from_raw_unchecked(EdgeMap::default().raw ^ 0b10000) };
+        assert_eq!(flipped.get(Edge::LU).1, EdgeOrientation::Flipped);
+        assert_eq!(
+            solver.search_with(with_edges(flipped), &options),
+            Err(SolveError::Unsolvable(Invariant::EdgeFlip))
+        );
+        let duplicate =
+            unsafe { EdgeMap::from_raw_unchecked(EdgeMap::default().raw & !0b1111 | 1) };
+        assert_eq!(
+            solver.search_with(with_edges(duplicate), &options),
+            Err(SolveError::InvalidMap(MapError::Duplicate))
+        );
+
+        let cube = Cube::random(&mut oorandom::Rand32::new(20));
+        let short = SolverOptions {
+            max_length: 8,
+            ..options
+        };
+        assert_eq!(
+            solver.search_with(cube, &short),
+            Err(SolveError::BudgetExhausted)
+        );
+        let hurried = SolverOptions {
+            time_budget: Some(Duration::from_secs(0)),
+            ..options
+        };
+        assert_eq!(
+            solver.search_with(cube, &hurried),
+            Err(SolveError::BudgetExhausted)
+        );
+        let solution = solver.search_with(cube, &options).unwrap();
+        assert!((cube * Cube::from(&solution)).is_solved());
+        assert_eq!(solver.search_with(Cube::default(), &options), Ok(vec![]));
+    }
+}
@@ -61,6 +61,7 @@ impl CubieCorners {
 }
 
 #[derive(Clone,Copy,Debug,Eq,PartialEq)]
+#[repr(C)]
 pub struct CubieCube {
     pub corners: CubieCorners,
     pub edges: CubieEdges,
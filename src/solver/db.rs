@@ -11,6 +11,8 @@ pub const N_MPERM: usize = 24;
 pub const N_COMB: usize = 140; //Search.USE_COMBP_PRUN
 pub const P2_PARITY_MOVE: usize = 0xA5; //Search.USE_COMBP_PRUN
 
+use std::convert::TryInto;
+use std::io::{self, Read, Write};
 use std::mem::swap;
 
 use super::coord::*;
@@ -33,6 +35,52 @@ macro_rules! unsafe_index {
         }
     };
 }
+/// The length of the header of [`CubeTable::write_to`].
+const TABLE_HEADER_LEN: usize = 8 + 16 + 2 + 2 + 8 + 8;
+
+/// The version of the layout and encoding of [`CubeTable`], to be increased
+/// whenever they change.
+const TABLE_LAYOUT_VERSION: u16 = 1;
+
+/// The header of a table of `size` bytes: a magic number, the versions of the
+/// crate and of the table layout, a byte order mark, the size and the
+/// checksum of the table.
+fn table_header(size: u64, checksum: u64) -> [u8; TABLE_HEADER_LEN] {
+    let mut header = [0; TABLE_HEADER_LEN];
+    header[..8].copy_from_slice(b"cubietbl");
+    // Tables built by another version might differ, even with the same size.
+    let version = env!("CARGO_PKG_VERSION").as_bytes();
+    header[8..8 + version.len()].copy_from_slice(version);
+    header[24..26].copy_from_slice(&TABLE_LAYOUT_VERSION.to_le_bytes());
+    header[26..28].copy_from_slice(&0x0102u16.to_ne_bytes());
+    header[28..36].copy_from_slice(&size.to_le_bytes());
+    header[36..].copy_from_slice(&checksum.to_le_bytes());
+    header
+}
+
+/// Whether every value is below `bound`.
+fn all_below<'a, T: Copy + Into<u64> + 'a>(
+    values: impl IntoIterator<Item = &'a T>,
+    bound: u64,
+) -> bool {
+    values.into_iter().all(|&value| value.into() < bound)
+}
+
+/// The 64-bit FNV-1a hash of `bytes`, a word at a time.
+fn checksum(bytes: &[u8]) -> u64 {
+    let mut words = bytes.chunks_exact(8);
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for word in &mut words {
+        hash ^= u64::from_le_bytes(word.try_into().unwrap());
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    for &byte in words.remainder() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
 fn fill_64xmask4(mask: u64) -> u64 {
     (((0x7777_7777_7777_7777 & mask) + 0x11111111_11111111) & mask) & 0x88888888_88888888
 }
@@ -42,6 +90,10 @@ fn fill_64xmask4(mask: u64) -> u64 {
 /// Contains symmetry-reduced coordinate move tables and pruning tables
 /// for both phases of the Kociemba algorithm. Constructed via
 /// [`CubeTableEventedBuilder`] or [`CubeTable::new`].
+///
+/// The layout is fixed so that [`CubeTable::write_to`] can store the table
+/// as its bytes.
+#[repr(C)]
 pub struct CubeTable {
     //PHASE 1
     pub ud_slice_twist_prun: [u64; (N_SLICE * N_TWIST_SYM) / 16 + 1],
@@ -49,8 +101,8 @@ pub struct CubeTable {
     pub mc_perm_prun: [u64; N_MPERM * N_PERM_SYM / 16 + 1],
     pub e_permccomb_prun: [u64; N_COMB * N_PERM_SYM / 16 + 1],
     pub twist_flip_prun: [u64; (N_FLIP * N_TWIST_SYM) / 16 + 1], //Search.USE_COMBP_PRUN
-    pub cube_sym: [CubieCube; 16],
-    pub move_cube: [CubieCube; 18],
+    pub move_cube_sym: [u64; 18],
+    pub first_move_sym: [u32; 48],
     pub ud_slice_conj: [[u16; 8]; N_SLICE],
     pub ud_slice_move: [[u16; N_MOVES]; N_SLICE],
     pub twist_move: [[u16; N_MOVES]; N_TWIST_SYM],
@@ -61,19 +113,10 @@ pub struct CubeTable {
     pub m_perm_conj: [[u16; 16]; N_MPERM],
     pub ccomb_p_move: [[u16; N_MOVES2]; N_COMB],
     pub ccomb_p_conj: [[u16; 16]; N_COMB],
-    pub move_cube_sym: [u64; 18],
-    pub first_move_sym: [u32; 48],
-    pub sym_mult: [[u8; 16]; 16],
-    pub sym_mult_inv: [[u8; 16]; 16],
-    pub sym_move: [[u8; 18]; 16],
-    pub sym_8move: [u8; 8 * 18],
-    pub sym_move_ud: [[u8; 18]; 16],
     pub flip_s2r: [u16; N_FLIP_SYM],
     pub twist_s2r: [u16; N_TWIST_SYM],
     pub eperm_s2r: [u16; N_PERM_SYM],
-    pub perm2combp: [u8; N_PERM_SYM],
     pub perm_inv_edge_sym: [u16; N_PERM_SYM],
-    pub m_perm_inv: [u8; N_MPERM],
     pub flip_r2s: [u16; N_FLIP],
     pub twist_r2s: [u16; N_TWIST],
     pub flip_s2rf: [u16; N_FLIP_SYM * 8],
@@ -81,8 +124,67 @@ pub struct CubeTable {
     pub sym_state_twist: [u16; N_TWIST_SYM],
     pub sym_state_flip: [u16; N_FLIP_SYM],
     pub sym_state_perm: [u16; N_PERM_SYM],
+    pub cube_sym: [CubieCube; 16],
+    pub move_cube: [CubieCube; 18],
+    pub sym_mult: [[u8; 16]; 16],
+    pub sym_mult_inv: [[u8; 16]; 16],
+    pub sym_move: [[u8; 18]; 16],
+    pub sym_8move: [u8; 8 * 18],
+    pub sym_move_ud: [[u8; 18]; 16],
+    pub perm2combp: [u8; N_PERM_SYM],
+    pub m_perm_inv: [u8; N_MPERM],
+    /// Rounds the size up to the alignment, so that no byte of the table is
+    /// padding.
+    padding: [u8; 6],
 }
 
+// `CubeTable::as_bytes` reads every byte of the table, so there must be no
+// padding between its fields.
+const _: () = {
+    use std::mem::size_of;
+    assert!(
+        size_of::<CubeTable>()
+            == size_of::<[u64; (N_SLICE * N_TWIST_SYM) / 16 + 1]>()
+                + size_of::<[u64; (N_SLICE * N_FLIP_SYM) / 16 + 1]>()
+                + size_of::<[u64; N_MPERM * N_PERM_SYM / 16 + 1]>()
+                + size_of::<[u64; N_COMB * N_PERM_SYM / 16 + 1]>()
+                + size_of::<[u64; (N_FLIP * N_TWIST_SYM) / 16 + 1]>()
+                + size_of::<[CubieCube; 16]>()
+                + size_of::<[CubieCube; 18]>()
+                + size_of::<[[u16; 8]; N_SLICE]>()
+                + size_of::<[[u16; N_MOVES]; N_SLICE]>()
+                + size_of::<[[u16; N_MOVES]; N_TWIST_SYM]>()
+                + size_of::<[[u16; N_MOVES]; N_FLIP_SYM]>()
+                + size_of::<[[u16; N_MOVES2]; N_PERM_SYM]>()
+                + size_of::<[[u16; N_MOVES2]; N_PERM_SYM]>()
+                + size_of::<[[u16; N_MOVES2]; N_MPERM]>()
+                + size_of::<[[u16; 16]; N_MPERM]>()
+                + size_of::<[[u16; N_MOVES2]; N_COMB]>()
+                + size_of::<[[u16; 16]; N_COMB]>()
+                + size_of::<[u64; 18]>()
+                + size_of::<[u32; 48]>()
+                + size_of::<[[u8; 16]; 16]>()
+                + size_of::<[[u8; 16]; 16]>()
+                + size_of::<[[u8; 18]; 16]>()
+                + size_of::<[u8; 8 * 18]>()
+                + size_of::<[[u8; 18]; 16]>()
+                + size_of::<[u16; N_FLIP_SYM]>()
+                + size_of::<[u16; N_TWIST_SYM]>()
+                + size_of::<[u16; N_PERM_SYM]>()
+                + size_of::<[u8; N_PERM_SYM]>()
+                + size_of::<[u16; N_PERM_SYM]>()
+                + size_of::<[u8; N_MPERM]>()
+                + size_of::<[u16; N_FLIP]>()
+                + size_of::<[u16; N_TWIST]>()
+                + size_of::<[u16; N_FLIP_SYM * 8]>()
+                + size_of::<[u16; N_PERM]>()
+                + size_of::<[u16; N_TWIST_SYM]>()
+                + size_of::<[u16; N_FLIP_SYM]>()
+                + size_of::<[u16; N_PERM_SYM]>()
+                + size_of::<[u8; 6]>()
+    );
+};
+
 impl CubeTable {
     #[inline]
     pub fn flip_sym_move(&self, flip: u16, mv: u8) -> u16 {
@@ -323,30 +425,7 @@ impl CubeTableEventedBuilder {
         if self.remaining <= 0 {
             return 0;
         }
-        match self.remaining {
-            7 => {
-                self.table.init_move();
-                self.table.init_sym();
-                self.table.init_perm_sym_2_raw();
-                self.table.init_cperm_move();
-                self.table.init_eperm_move();
-                self.table.init_mperm_move_conj();
-                self.table.init_combpmove_conj();
-                self.table.init_flip_sym_2_raw();
-            }
-            6 => {
-                self.table.init_twist_sym_2_raw();
-                self.table.init_flip_move();
-                self.table.init_twist_move();
-                self.table.init_ud_slice_move_conj();
-            }
-            5 => self.table.init_mc_perm_prun(),
-            4 => self.table.init_perm_compb_prun(),
-            3 => self.table.init_slice_twist_prun(),
-            2 => self.table.init_slice_flip_prun(),
-            1 => self.table.init_twist_flip_prun(),
-            _ => return 0,
-        };
+        self.table.init_step(self.remaining);
         self.remaining -= 1;
         self.remaining
     }
@@ -364,6 +443,137 @@ impl CubeTable {
         unsafe { Box::from_raw(ptr.cast()) }
     }
 
+    /// Performs the step of [`CubeTableEventedBuilder::next`] done with
+    /// `step` steps remaining, from 7 down to 1.
+    pub fn init_step(&mut self, step: i32) {
+        match step {
+            7 => {
+                self.init_move();
+                self.init_sym();
+                self.init_perm_sym_2_raw();
+                self.init_cperm_move();
+                self.init_eperm_move();
+                self.init_mperm_move_conj();
+                self.init_combpmove_conj();
+                self.init_flip_sym_2_raw();
+            }
+            6 => {
+                self.init_twist_sym_2_raw();
+                self.init_flip_move();
+                self.init_twist_move();
+                self.init_ud_slice_move_conj();
+            }
+            5 => self.init_mc_perm_prun(),
+            4 => self.init_perm_compb_prun(),
+            3 => self.init_slice_twist_prun(),
+            2 => self.init_slice_flip_prun(),
+            1 => self.init_twist_flip_prun(),
+            _ => (),
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        // The table is only made of integers, without padding between them.
+        let ptr = (self as *const CubeTable).cast();
+        unsafe { std::slice::from_raw_parts(ptr, std::mem::size_of::<CubeTable>()) }
+    }
+
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        let ptr = (self as *mut CubeTable).cast();
+        unsafe { std::slice::from_raw_parts_mut(ptr, std::mem::size_of::<CubeTable>()) }
+    }
+
+    /// Writes the table after a header with the version of the crate, the
+    /// size of the table and a checksum, to be read by
+    /// [`CubeTable::read_from`].
+    ///
+    /// The table is written in the byte order of the machine.
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        let bytes = self.as_bytes();
+        writer.write_all(&table_header(bytes.len() as u64, checksum(bytes)))?;
+        writer.write_all(bytes)
+    }
+
+    /// Reads a table written by [`CubeTable::write_to`].
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] if the table was written by
+    /// another version of the crate or on a machine with another byte order,
+    /// or if it is corrupted. Since the solver indexes arrays with values of
+    /// the table without checking bounds, every such value is checked to be
+    /// in range, so that crafted data is rejected too.
+    pub fn read_from(reader: &mut impl Read) -> io::Result<Box<CubeTable>> {
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut header = [0; TABLE_HEADER_LEN];
+        reader.read_exact(&mut header)?;
+        let size = std::mem::size_of::<CubeTable>() as u64;
+        let expected = table_header(size, 0);
+        if header[..8] != expected[..8] {
+            return Err(invalid("not a table file"));
+        }
+        // Everything but the checksum is known in advance.
+        let (fields, sum) = header.split_at(TABLE_HEADER_LEN - 8);
+        if *fields != expected[..TABLE_HEADER_LEN - 8] {
+            return Err(invalid("tables of another version, byte order or size"));
+        }
+        let mut table = CubeTable::new_uninit();
+        reader.read_exact(table.as_bytes_mut())?;
+        if checksum(table.as_bytes()).to_le_bytes() != sum {
+            return Err(invalid("corrupted tables"));
+        }
+        if !table.indices_in_range() {
+            return Err(invalid("table indices out of range"));
+        }
+        Ok(table)
+    }
+
+    /// Whether the values used as indices are within the ranges the solver
+    /// relies on, as they are in built tables.
+    fn indices_in_range(&self) -> bool {
+        let (twist_sym, flip_sym, perm_sym) = (
+            (N_TWIST_SYM << 3) as u64,
+            (N_FLIP_SYM << 3) as u64,
+            (N_PERM_SYM << 4) as u64,
+        );
+        // Symmetries of UD moves are UD moves, which the phase 2 tables are
+        // indexed with.
+        let ud_moves_in_range = self.sym_move_ud.iter().all(|moves| {
+            all_below(&moves[..N_MOVES2], N_MOVES2 as u64) && all_below(&moves[..], N_MOVES as u64)
+        });
+        let cubes_in_range = self
+            .cube_sym
+            .iter()
+            .chain(&self.move_cube)
+            .all(|cube| all_below(&cube.corners.raw, 6 << 3) && all_below(&cube.edges.raw, 24));
+        ud_moves_in_range
+            && cubes_in_range
+            && all_below(self.ud_slice_conj.iter().flatten(), N_SLICE as u64)
+            && all_below(self.ud_slice_move.iter().flatten(), N_SLICE as u64)
+            && all_below(self.twist_move.iter().flatten(), twist_sym)
+            && all_below(self.flip_move.iter().flatten(), flip_sym)
+            && all_below(self.c_perm_move.iter().flatten(), perm_sym)
+            && all_below(self.e_perm_move.iter().flatten(), perm_sym)
+            && all_below(self.m_perm_move.iter().flatten(), N_MPERM as u64)
+            && all_below(self.m_perm_conj.iter().flatten(), N_MPERM as u64)
+            && all_below(self.ccomb_p_move.iter().flatten(), N_COMB as u64)
+            && all_below(self.ccomb_p_conj.iter().flatten(), N_COMB as u64)
+            && all_below(&self.flip_s2r, N_FLIP as u64)
+            && all_below(&self.twist_s2r, N_TWIST as u64)
+            && all_below(&self.eperm_s2r, N_PERM as u64)
+            && all_below(&self.perm_inv_edge_sym, perm_sym)
+            && all_below(&self.flip_r2s, flip_sym)
+            && all_below(&self.twist_r2s, twist_sym)
+            && all_below(&self.flip_s2rf, N_FLIP as u64)
+            && all_below(&self.eperm_r2s, perm_sym)
+            && all_below(&self.sym_state_twist, 1 << 8)
+            && all_below(&self.sym_state_flip, 1 << 8)
+            && all_below(self.sym_mult.iter().flatten(), 16)
+            && all_below(self.sym_mult_inv.iter().flatten(), 16)
+            && all_below(self.sym_move.iter().flatten(), N_MOVES as u64)
+            && all_below(&self.sym_8move, N_MOVES as u64)
+            && all_below(&self.perm2combp, N_COMB as u64)
+            && all_below(&self.m_perm_inv, N_MPERM as u64)
+    }
+
     fn init_ud_slice_move_conj(&mut self) {
         let mut c = CubieCube::default();
         let mut d = CubieCube::default();
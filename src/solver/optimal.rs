@@ -0,0 +1,162 @@
+//! Optimal solving with IDA* over the phase 1 pruning tables.
+//!
+//! The distance to the phase 1 subgroup of each of the three axes is a lower
+//! bound on the distance to solved, so the largest of them bounds an
+//! iterative deepening search that only stops at the solved state.
+use super::coord::*;
+use super::cube::{CubieCube, URF_MOVE};
+use super::db::CubeTable;
+use std::cmp::max;
+use std::time::Instant;
+
+#[derive(Clone, Copy)]
+struct Node {
+    /// The phase 1 coordinates of the cube seen along each axis.
+    axes: [Phase1Cube; 3],
+    cube: CubieCube,
+}
+
+pub struct OptimalSearch<'a> {
+    db: &'a CubeTable,
+    /// For each axis, the move of the conjugated cube matching each move.
+    axis_moves: [[u8; 18]; 3],
+    moves: Vec<u8>,
+    /// The time after which the search gives up.
+    pub deadline: Option<Instant>,
+    /// The moves a solution may not use, as a mask over the solver's
+    /// numbering.
+    pub skipped_moves: u32,
+    nodes: u64,
+    expired: bool,
+}
+
+impl<'a> OptimalSearch<'a> {
+    pub fn new(db: &'a CubeTable) -> OptimalSearch<'a> {
+        let mut axis_moves = [[0; 18]; 3];
+        for (axis, table) in axis_moves.iter_mut().enumerate() {
+            for (conjugated, &mv) in URF_MOVE[axis].iter().enumerate() {
+                table[mv as usize] = conjugated as u8;
+            }
+        }
+        OptimalSearch {
+            db,
+            axis_moves,
+            moves: Vec::new(),
+            deadline: None,
+            skipped_moves: 0,
+            nodes: 0,
+            expired: false,
+        }
+    }
+
+    fn bound(&self, node: &Node) -> u32 {
+        let db = self.db;
+        node.axes
+            .iter()
+            .map(|axis| {
+                max(
+                    max(
+                        db.depth_bound(axis.twist, axis.flip),
+                        db.depth_bound(axis.twistc, axis.flipc),
+                    ),
+                    max(
+                        prune_value(db, axis.slice, axis.flip) as i32,
+                        prune_value(db, axis.slice, axis.twist) as i32,
+                    ),
+                ) as u32
+            })
+            .max()
+            .unwrap()
+    }
+
+    fn turn(&self, node: &Node, mv: u8) -> Node {
+        let db = self.db;
+        let mut next = *node;
+        for (axis, next) in next.axes.iter_mut().enumerate() {
+            let turn = Turn(self.axis_moves[axis][mv as usize]);
+            next.twist = next.twist.turn(db, turn);
+            next.twistc = next.twistc.turn(db, turn.conjugate(db));
+            next.flip = next.flip.turn(db, turn);
+            next.flipc = next.flipc.turn(db, turn.conjugate(db));
+            next.slice = next.slice.turn(db, turn);
+        }
+        let move_cube = &db.move_cube[mv as usize];
+        next.cube.corners = node.cube.corners.mult(move_cube.corners);
+        next.cube.edges = node.cube.edges.mult(&move_cube.edges);
+        next
+    }
+
+    /// Finds a shortest solution of at most `max_depth` moves, in the
+    /// solver's move numbering, calling `progress` with each depth proven to
+    /// have no solution. Returns `None` when there is no such solution or
+    /// the deadline passes.
+    pub fn solve(
+        &mut self,
+        cc: CubieCube,
+        max_depth: u32,
+        progress: &mut dyn FnMut(u32),
+    ) -> Option<Vec<u8>> {
+        let mut node = Node {
+            axes: [Phase1Cube::default(); 3],
+            cube: cc,
+        };
+        let mut conjugated = cc;
+        for axis in &mut node.axes {
+            axis.set_with_prun(self.db, &conjugated, 20);
+            conjugated.urf_conjugate();
+        }
+        let mut depth = self.bound(&node);
+        while depth <= max_depth {
+            self.moves.clear();
+            if self.search(&node, depth, 18) {
+                return Some(self.moves.iter().rev().copied().collect());
+            }
+            if self.expired {
+                return None;
+            }
+            progress(depth);
+            depth += 1;
+        }
+        None
+    }
+
+    /// Whether the search gave up because of the deadline.
+    pub fn expired(&self) -> bool {
+        self.expired
+    }
+
+    fn search(&mut self, node: &Node, depth: u32, last: u8) -> bool {
+        if depth == 0 {
+            return node.cube == CubieCube::default();
+        }
+        // Reading the clock on every node would slow the search down.
+        self.nodes += 1;
+        if self.nodes % 4096 == 0 {
+            self.expired |= self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        if self.expired {
+            return false;
+        }
+        for mv in 0..18 {
+            let face = mv / 3;
+            // Moves of opposite faces commute, keep only one order.
+            if last < 18 && (face == last / 3 || face + 3 == last / 3) {
+                continue;
+            }
+            if (self.skipped_moves >> mv) & 1 != 0 {
+                continue;
+            }
+            let next = self.turn(node, mv);
+            if self.bound(&next) >= depth {
+                continue;
+            }
+            if self.search(&next, depth - 1, mv) {
+                self.moves.push(mv);
+                return true;
+            }
+        }
+        false
+    }
+}
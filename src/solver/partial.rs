@@ -0,0 +1,334 @@
+//! Solving only the stickers of a tile map that are specified.
+//!
+//! A face turn moves each sticker to another tile whatever its color, so the
+//! pieces with a specified sticker can be followed without knowing anything
+//! about the others. The distance of each pair of these pieces to a state
+//! where both are home is a lower bound for an IDA* search, found by a
+//! breadth first search over the 576 places of the pair.
+use super::FACE_MOVES;
+use crate::tile::{tiles_from_corner, tiles_from_edge};
+use crate::{Corner, Edge, Face, FixedCentersCube, MapError, TileMap};
+use std::time::Instant;
+
+/// The most pieces a tile map has stickers on.
+const MAX_PIECES: usize = 20;
+
+/// The index of a corner or edge tile among the 24 tiles of its kind, by its
+/// index on the face.
+const SLOT: [usize; 9] = [0, 0, 1, 1, 0, 2, 2, 3, 3];
+
+fn slot(tile: u8) -> usize {
+    tile as usize / 9 * 4 + SLOT[tile as usize % 9]
+}
+
+/// The tiles of each corner and edge.
+fn pieces() -> Vec<Vec<usize>> {
+    Corner::corners()
+        .map(|corner| tiles_from_corner(corner).to_vec())
+        .chain(Edge::edges().map(|edge| {
+            let (a, b) = tiles_from_edge(edge);
+            vec![a, b]
+        }))
+        .collect()
+}
+
+/// The tile each face turn moves the sticker of each tile to, in the
+/// solver's move numbering.
+fn tile_moves() -> [[u8; 54]; 18] {
+    let pieces = pieces();
+    let solved = TileMap::from(FixedCentersCube::default());
+    let colors = |map: &TileMap, piece: &[usize]| {
+        let mut colors: Vec<_> = piece.iter().map(|&tile| map.as_array()[tile]).collect();
+        colors.sort_by_key(|color| color.map(|face| face as u8));
+        colors
+    };
+    let mut moves = [[0; 54]; 18];
+    for (dest, &mv) in moves.iter_mut().zip(FACE_MOVES.iter()) {
+        let turned = TileMap::from(FixedCentersCube::default() * mv);
+        for (tile, dest) in dest.iter_mut().enumerate() {
+            *dest = tile as u8;
+        }
+        // The stickers of a piece have distinct colors, so each one goes to
+        // the tile of its color on the piece with the same colors.
+        for piece in &pieces {
+            let moved = pieces
+                .iter()
+                .find(|other| colors(&turned, other) == colors(&solved, piece))
+                .unwrap();
+            for &tile in piece {
+                let color = solved.as_array()[tile];
+                dest[tile] = *moved
+                    .iter()
+                    .find(|&&other| turned.as_array()[other] == color)
+                    .unwrap() as u8;
+            }
+        }
+    }
+    moves
+}
+
+/// A piece with a specified sticker, followed by the tile of its first
+/// sticker, or slot, among the 24 tiles of its kind.
+struct Piece {
+    /// The tile of each slot.
+    tiles: [u8; 24],
+    /// Whether every sticker of the piece is home from each slot.
+    home: [bool; 24],
+    /// The smallest tile of the corner or edge the piece is on from each slot.
+    place: [u8; 24],
+}
+
+/// The number of moves bringing a few pieces home from each of their slots.
+struct PatternTable {
+    pieces: Vec<usize>,
+    depths: Vec<u8>,
+}
+
+#[derive(Clone, Copy)]
+struct Node {
+    /// The tile of the first sticker of each piece followed.
+    tiles: [u8; MAX_PIECES],
+}
+
+pub struct PartialSearch {
+    moves: [[u8; 54]; 18],
+    root: Node,
+    len: usize,
+    tables: Vec<PatternTable>,
+    solution: Vec<u8>,
+    /// The time after which the search gives up.
+    pub deadline: Option<Instant>,
+    /// The moves a solution may not use, as a mask over the solver's
+    /// numbering.
+    pub skipped_moves: u32,
+    nodes: u64,
+    expired: bool,
+}
+
+impl PartialSearch {
+    /// Prepares the search for the stickers of `stickers`, given by the face
+    /// each one belongs on.
+    ///
+    /// Fails with [`MapError::OutOfBounds`] if the stickers of a piece do not
+    /// belong to any piece, and with [`MapError::Duplicate`] if two pieces
+    /// cannot be home at once.
+    pub fn new(stickers: &[Option<Face>; 54]) -> Result<PartialSearch, MapError> {
+        let moves = tile_moves();
+        let mut corners = [0; 6];
+        let mut edges = [0; 6];
+        for (tile, sticker) in stickers.iter().enumerate() {
+            match (tile % 9, sticker) {
+                (4, _) | (_, None) => (),
+                (i, Some(face)) if i % 2 == 0 => corners[*face as usize] += 1,
+                (_, Some(face)) => edges[*face as usize] += 1,
+            }
+        }
+        if corners.iter().chain(edges.iter()).any(|&count| count > 4) {
+            return Err(MapError::Duplicate);
+        }
+
+        let pieces: Vec<_> = pieces()
+            .into_iter()
+            .filter(|piece| piece.iter().any(|&tile| stickers[tile].is_some()))
+            .collect();
+        let mut root = Node {
+            tiles: [0; MAX_PIECES],
+        };
+        for (i, tiles) in pieces.iter().enumerate() {
+            root.tiles[i] = tiles[0] as u8;
+        }
+        let pieces: Vec<_> = pieces
+            .iter()
+            .map(|tiles| Piece::new(&moves, stickers, tiles))
+            .collect();
+        let mut tables = Vec::new();
+        for a in 0..pieces.len() {
+            tables.push(PatternTable::new(&moves, &pieces, vec![a]).ok_or(MapError::OutOfBounds)?);
+        }
+        for a in 0..pieces.len() {
+            for b in a + 1..pieces.len() {
+                tables.push(
+                    PatternTable::new(&moves, &pieces, vec![a, b]).ok_or(MapError::Duplicate)?,
+                );
+            }
+        }
+        Ok(PartialSearch {
+            moves,
+            root,
+            len: pieces.len(),
+            tables,
+            solution: Vec::new(),
+            deadline: None,
+            skipped_moves: 0,
+            nodes: 0,
+            expired: false,
+        })
+    }
+
+    fn bound(&self, node: &Node) -> u32 {
+        self.tables
+            .iter()
+            .map(|table| table.depth(node))
+            .max()
+            .unwrap_or(0) as u32
+    }
+
+    fn turn(&self, node: &Node, mv: u8) -> Node {
+        let mut next = *node;
+        for tile in &mut next.tiles[..self.len] {
+            *tile = self.moves[mv as usize][*tile as usize];
+        }
+        next
+    }
+
+    /// Finds a shortest sequence of at most `max_depth` moves bringing every
+    /// sticker home, in the solver's move numbering. Returns `None` when
+    /// there is no such sequence or the deadline passes.
+    pub fn solve(&mut self, max_depth: u32) -> Option<Vec<u8>> {
+        let root = self.root;
+        let mut depth = self.bound(&root);
+        while depth <= max_depth {
+            self.solution.clear();
+            if self.search(&root, depth, 18) {
+                return Some(self.solution.iter().rev().copied().collect());
+            }
+            if self.expired {
+                return None;
+            }
+            depth += 1;
+        }
+        None
+    }
+
+    fn search(&mut self, node: &Node, depth: u32, last: u8) -> bool {
+        if depth == 0 {
+            return self.bound(node) == 0;
+        }
+        // Reading the clock on every node would slow the search down.
+        self.nodes += 1;
+        if self.nodes % 4096 == 0 {
+            self.expired |= self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline);
+        }
+        if self.expired {
+            return false;
+        }
+        for mv in 0..18 {
+            let face = mv / 3;
+            // Moves of opposite faces commute, keep only one order.
+            if last < 18 && (face == last / 3 || face + 3 == last / 3) {
+                continue;
+            }
+            if (self.skipped_moves >> mv) & 1 != 0 {
+                continue;
+            }
+            let next = self.turn(node, mv);
+            if self.bound(&next) >= depth {
+                continue;
+            }
+            if self.search(&next, depth - 1, mv) {
+                self.solution.push(mv);
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Piece {
+    fn new(moves: &[[u8; 54]; 18], stickers: &[Option<Face>; 54], tiles: &[usize]) -> Piece {
+        let mut piece = Piece {
+            tiles: [0; 24],
+            home: [false; 24],
+            place: [0; 24],
+        };
+        let mut seen = [false; 24];
+        let mut queue = vec![tiles.iter().map(|&tile| tile as u8).collect::<Vec<_>>()];
+        while let Some(moved) = queue.pop() {
+            let index = slot(moved[0]);
+            if seen[index] {
+                continue;
+            }
+            seen[index] = true;
+            piece.tiles[index] = moved[0];
+            piece.home[index] = tiles
+                .iter()
+                .zip(moved.iter())
+                .all(|(&from, &to)| stickers[from].map_or(true, |face| face as u8 == to / 9));
+            piece.place[index] = *moved.iter().min().unwrap();
+            for mv in moves {
+                queue.push(moved.iter().map(|&tile| mv[tile as usize]).collect());
+            }
+        }
+        piece
+    }
+}
+
+impl PatternTable {
+    /// Searches breadth first from the states where all of `pieces` are
+    /// home at once, or returns `None` if there is no such state.
+    fn new(moves: &[[u8; 54]; 18], all: &[Piece], pieces: Vec<usize>) -> Option<PatternTable> {
+        let slots = |mut index: usize| {
+            pieces.iter().map(move |_| {
+                let slot = index % 24;
+                index /= 24;
+                slot
+            })
+        };
+        let size = 24usize.pow(pieces.len() as u32);
+        let mut depths = vec![u8::MAX; size];
+        let mut frontier = Vec::new();
+        for (index, depth) in depths.iter_mut().enumerate() {
+            let places: Vec<_> = slots(index)
+                .zip(&pieces)
+                .map(|(slot, &piece)| all[piece].place[slot])
+                .collect();
+            let home = slots(index)
+                .zip(&pieces)
+                .all(|(slot, &piece)| all[piece].home[slot]);
+            let apart = places
+                .iter()
+                .enumerate()
+                .all(|(i, place)| !places[..i].contains(place));
+            if home && apart {
+                *depth = 0;
+                frontier.push(index);
+            }
+        }
+        if frontier.is_empty() {
+            return None;
+        }
+        let mut depth = 0;
+        // The inverse of each move is a move too, so the depths from home
+        // are the depths to it.
+        while !frontier.is_empty() {
+            depth += 1;
+            let mut next = Vec::new();
+            for index in frontier {
+                for mv in moves {
+                    let to: Vec<_> = slots(index)
+                        .zip(&pieces)
+                        .map(|(from, &piece)| slot(mv[all[piece].tiles[from] as usize]))
+                        .collect();
+                    let to = to.iter().rev().fold(0, |to, slot| to * 24 + slot);
+                    if depths[to] == u8::MAX {
+                        depths[to] = depth;
+                        next.push(to);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        Some(PatternTable { pieces, depths })
+    }
+
+    fn depth(&self, node: &Node) -> u8 {
+        let index = self
+            .pieces
+            .iter()
+            .rev()
+            .fold(0, |index, &piece| index * 24 + slot(node.tiles[piece]));
+        self.depths[index]
+    }
+}
@@ -1,11 +1,12 @@
 use std::cmp::{min,max};
 use super::coord::Phase1Cube;
 use super::coord::*;
-use super::db::CubeTable;
+use super::db::{CubeTable, N_MOVES2, N_MPERM};
 
 use super::solution::Solution;
 use super::util;
-use super::cube::CubieCube;
+use super::cube::{CubieCube, URF_MOVE};
+use std::time::Instant;
 
 const MAX_PRE_MOVES:u32 = 20;
 const MIN_P1LENGTH_PRE:u32 = 7;
@@ -24,6 +25,17 @@ pub struct Search {
     probe:u64,
     probe_max:u64,
     probe_min:u64,
+    /// The time after which the search stops probing.
+    pub deadline: Option<Instant>,
+    /// The moves a solution may not use, as a mask over the solver's
+    /// numbering.
+    pub skipped_moves: u32,
+    /// The moves of the conjugated cube matching the skipped moves, in the
+    /// phase 1 and phase 2 numberings.
+    phase1_skip: u32,
+    phase2_skip: u16,
+    /// The slice permutations phase 2 can solve without the skipped moves.
+    phase2_slices: u32,
     self_sym: u64,
 
     cc: CubieCube,
@@ -45,6 +57,8 @@ pub struct Search {
 impl Search {
 
 
+   /// Returns `None` when no solution of at most `max_depth` moves is found
+   /// within the probe limits or before the deadline.
    pub fn solve_cc(&mut self, db: &CubeTable, cc: CubieCube, max_depth: u32, probe_max: u64, probe_min: u64, verbose: u32) -> Option<Vec<u8>> {
        self.cc = cc;
        self.sol_len = max_depth + 1;
@@ -56,7 +70,23 @@ impl Search {
        self.is_rec = false;
 
        self.init_search(db);
-       self.search(db)
+       let solution = self.search(db);
+       // The search stops with an empty solution when it gives up.
+       if self.sol_len > max_depth { None } else { solution }
+    }
+
+   /// Resumes the last search where it found its solution, looking for a
+   /// strictly shorter one. Returns `None` when none is found within the
+   /// probe limits or before the deadline.
+   pub fn next(&mut self, db: &CubeTable, probe_max: u64, probe_min: u64) -> Option<Vec<u8>> {
+       self.probe = 0;
+       self.probe_max = probe_max;
+       self.probe_min = probe_min;
+       self.solution.len = 0;
+       self.is_rec = true;
+
+       let solution = self.search(db);
+       if self.solution.len == 0 { None } else { solution }
     }
     fn search(&mut self, db: &CubeTable) -> Option<Vec<u8>> {
         self.length1 = if self.is_rec { self.length1 } else { 0};
@@ -70,18 +100,52 @@ impl Search {
                     self.urf_idx +=1;
                     continue;
                 }
+                self.conjugate_skipped_moves(db);
                 if self.phase1_pre_moves(db, self.max_pre_moves as u32, -12, self.urf_cubie[self.urf_idx as usize], (self.self_sym & 0xffff) as u32) == 0 {
                     return Some(self.solution.to_vec());
                 }
+                // Only the step the search resumes from skips what it explored.
+                self.is_rec = false;
                 self.urf_idx+=1;
             }
             self.length1+=1;
         }
         Some(self.solution.to_vec())
     }
+    fn conjugate_skipped_moves(&mut self, db: &CubeTable) {
+        self.phase1_skip = 0;
+        for (m, &mv) in URF_MOVE[self.urf_idx as usize].iter().enumerate() {
+            self.phase1_skip |= ((self.skipped_moves >> mv) & 1) << m;
+        }
+        self.phase2_skip = 0;
+        for (m, &mv) in util::UD2STD.iter().enumerate().take(N_MOVES2) {
+            self.phase2_skip |= (((self.phase1_skip >> mv) & 1) << m) as u16;
+        }
+        // Without two of the half turns of the sides, some slice edges can
+        // no longer reach each other. Otherwise the allowed moves generate
+        // the whole phase 2 group, as long as both quarter turns remain.
+        self.phase2_slices = 1;
+        let mut grown = true;
+        while grown {
+            grown = false;
+            for perm in 0..N_MPERM {
+                if (self.phase2_slices >> perm) & 1 == 0 {
+                    continue;
+                }
+                for m in 0..N_MOVES2 {
+                    let next = db.m_perm_move[perm][m];
+                    if (self.phase2_skip >> m) & 1 == 0 && (self.phase2_slices >> next) & 1 == 0 {
+                        self.phase2_slices |= 1 << next;
+                        grown = true;
+                    }
+                }
+            }
+        }
+    }
     fn init_search(&mut self, db: &CubeTable) {
         self.conj_mask = 0;
-        self.self_sym = self.cc.self_symmetry(db);
+        // The symmetries of the cube may not preserve the allowed moves.
+        self.self_sym = if self.skipped_moves == 0 { self.cc.self_symmetry(db) } else { 0 };
         if ((self.self_sym >> 16) & 0xffff) != 0 { self.conj_mask |= 0x12;}
         if ((self.self_sym >> 32) & 0xffff) != 0 { self.conj_mask |= 0x24;}
         if ((self.self_sym >> 48) & 0xffff) != 0 { self.conj_mask |= 0x38;}
@@ -149,7 +213,7 @@ impl Search {
         // }
 //        eprintln!("{} {}",(18-lmx), lmx);
         let axis_skip = 0b111000000111000000111000000000u32>>(18-lmx);
-        skip_moves |= axis_skip;
+        skip_moves |= axis_skip | self.phase1_skip;
         let mut move_mask = (!(skip_moves)) & 0x3ffff; // TODO OPTIMIZE SO WE DON"T HAVE TO INVETR"
         while move_mask != 0 {
             let m = move_mask.trailing_zeros() as i32;
@@ -173,7 +237,7 @@ impl Search {
     fn init_phase2_pre(&mut self, db: &CubeTable) -> u32 {
         self.is_rec = false;
         let probe_bound = if self.solution.len > 0 { self.probe_min } else { self.probe_max};
-        if self.probe >= probe_bound {
+        if self.probe >= probe_bound || self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
             return 0;
         }
         self.probe+=1;
@@ -280,6 +344,9 @@ impl Search {
 
     fn init_phase2(&mut self, db: &CubeTable, corn: SymCPerm, edge: SymEPerm, mid: MPerm,
                      edge_inv:SymEPerm, corn_inv:SymCPerm)  -> u32{
+        if (self.phase2_slices >> mid.0) & 1 == 0 {
+            return 1;
+        }
         let prun = max(prune_value(db, corn_inv, edge_inv),
                        max(prune_value(db, mid, corn),
                            prune_value(db, corn, edge))) as u32;
@@ -330,7 +397,7 @@ impl Search {
             }
         }
 
-        let mut turns = TurnIterator::new(lm); 
+        let mut turns = TurnIterator::new(lm, self.phase1_skip);
         let mut next = Phase1Cube::default();
         
         while let Some(turn) = turns.next() {
@@ -397,7 +464,7 @@ impl Search {
             return max_depth;
         }
 
-        let mut turns = UDTurnIterator::new(cube.last_turn);
+        let mut turns = UDTurnIterator::new(cube.last_turn, self.phase2_skip);
         while let Some(turn) = turns.next() {
             let next_cube = cube.turn(db, turn);
 
@@ -507,9 +574,9 @@ struct TurnIterator {
 }
 
 impl TurnIterator {
-    fn new(previous_turn: i32) -> TurnIterator {
+    fn new(previous_turn: i32, skipped: u32) -> TurnIterator {
         TurnIterator{
-            turn_mask: (!( 0b000000000111000000111000000000u32>>(18-previous_turn)) & 0x3ffff),
+            turn_mask: (!( 0b000000000111000000111000000000u32>>(18-previous_turn) | skipped) & 0x3ffff),
         }
     }
     fn skip_turn_axis(&mut self, turn: u8) {
@@ -529,9 +596,9 @@ struct UDTurnIterator {
     turn_mask: u16,
 }
 impl UDTurnIterator {
-    fn new(previous_turn: UDTurn) -> UDTurnIterator {
+    fn new(previous_turn: UDTurn, skipped: u16) -> UDTurnIterator {
         UDTurnIterator{
-            turn_mask:  (!util::CKMV2BIT[previous_turn.0 as usize])&0b11_111_11_111_u16,
+            turn_mask:  (!(util::CKMV2BIT[previous_turn.0 as usize] | skipped))&0b11_111_11_111_u16,
         }
     }
     fn skip_turn_axis(&mut self, turn: UDTurn) {
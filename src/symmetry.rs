@@ -0,0 +1,490 @@
+//! The 48 symmetries of the cube, rotations and reflections, and the
+//! conjugation of cube states and moves by them.
+//!
+//! A symmetry is a whole-cube rotation, optionally preceded by a reflection
+//! through the M plane (the plane between the left and right faces).
+//! Conjugating a state by a symmetry gives the state seen through it:
+//! rotating an algorithm, or mirroring it left to right.
+//!
+//! ```
+//! use cubie::{Cube, Move::*, Symmetry};
+//!
+//! let sune = Cube::from(&[R1, U1, R3, U1, R1, U2, R3]);
+//! let left_sune = Cube::from(&[L3, U3, L1, U3, L3, U2, L1]);
+//! assert_eq!(sune.conjugate(Symmetry::MIRROR), left_sune);
+//! ```
+use crate::moves::ROTATION_TABLE;
+use crate::{CenterMap, CornerMap, Cube, EdgeMap, Face, FixedCentersCube, Move};
+
+/// An element of the full octahedral symmetry group of the cube.
+///
+/// Each symmetry is identified by an index from 0 to 47. The first 24 are the
+/// whole-cube rotations, starting with [Symmetry::IDENTITY] and ordered by
+/// [CenterMap::index] of the rotated centers, the last 24 are the same
+/// rotations preceded by the left-right reflection, starting with
+/// [Symmetry::MIRROR].
+///
+/// Symmetries compose via multiplication in the same order as cube states:
+/// conjugating by `a * b` is the same as conjugating by `a` then by `b`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(transparent)]
+pub struct Symmetry(u8);
+
+/// The reflection of a corner position in the M plane.
+const fn mirror_corner(corner: u64) -> u64 {
+    corner ^ 0b100
+}
+
+/// The reflection of each edge position in the M plane.
+const MIRROR_EDGE: [u8; 12] = [2, 3, 0, 1, 7, 6, 5, 4, 8, 9, 10, 11];
+
+/// The [CenterMap::index] of the solved centers, which the indices of the
+/// rotations are offset by so that the identity comes first.
+const IDENTITY_CENTERS: u8 = 18;
+
+impl Symmetry {
+    /// Number of symmetries.
+    pub const COUNT: u8 = 48;
+
+    /// The symmetry leaving every state unchanged.
+    pub const IDENTITY: Symmetry = Symmetry(0);
+
+    /// The reflection swapping the left and right faces.
+    pub const MIRROR: Symmetry = Symmetry(24);
+
+    /// Builds a symmetry from a whole-cube rotation, optionally preceded by
+    /// a reflection through the M plane.
+    pub fn new(rotation: CenterMap, reflection: bool) -> Symmetry {
+        Symmetry((rotation.index() + 24 - IDENTITY_CENTERS) % 24 + 24 * reflection as u8)
+    }
+
+    /// The symmetry with the given index, if `index < 48`.
+    pub fn from_index(index: u8) -> Option<Symmetry> {
+        if index < Symmetry::COUNT {
+            Some(Symmetry(index))
+        } else {
+            None
+        }
+    }
+
+    /// A dense index of the symmetry in `0..48`.
+    #[inline]
+    pub fn index(self) -> u8 {
+        self.0
+    }
+
+    /// An iterator over all 48 symmetries.
+    pub fn symmetries() -> impl Iterator<Item = Symmetry> {
+        (0..Symmetry::COUNT).map(Symmetry)
+    }
+
+    /// An iterator over the 24 symmetries that are rotations.
+    pub fn rotations() -> impl Iterator<Item = Symmetry> {
+        (0..24).map(Symmetry)
+    }
+
+    /// The whole-cube rotation applied after the optional reflection.
+    #[inline]
+    pub fn rotation(self) -> CenterMap {
+        self.rotation_cube().centers()
+    }
+
+    /// The whole-cube rotation as a cube state.
+    #[inline]
+    fn rotation_cube(self) -> Cube {
+        ROTATION_TABLE[((self.0 + IDENTITY_CENTERS) % 24) as usize]
+    }
+
+    /// Returns true if the symmetry reverses handedness, turning clockwise
+    /// moves into counter-clockwise ones.
+    #[inline]
+    pub fn is_reflection(self) -> bool {
+        self.0 >= 24
+    }
+
+    /// The face a move of `face` is mapped to under [Move::conjugate].
+    pub fn face(self, face: Face) -> Face {
+        let face = if self.is_reflection() {
+            mirror_face(face)
+        } else {
+            face
+        };
+        self.rotation().get(face)
+    }
+
+    /// The symmetry undoing `self`.
+    pub fn inverse(self) -> Symmetry {
+        let rotation = self.rotation_cube();
+        let rotation = if self.is_reflection() {
+            mirror(rotation)
+        } else {
+            rotation
+        };
+        Symmetry::new(rotation.centers().inverse(), self.is_reflection())
+    }
+}
+
+impl std::ops::Mul for Symmetry {
+    type Output = Symmetry;
+    fn mul(self, rhs: Symmetry) -> Symmetry {
+        let rotation = self.rotation_cube();
+        let rotation = if rhs.is_reflection() {
+            mirror(rotation)
+        } else {
+            rotation
+        };
+        Symmetry::new(
+            rotation.centers() * rhs.rotation(),
+            self.is_reflection() ^ rhs.is_reflection(),
+        )
+    }
+}
+
+impl std::ops::MulAssign for Symmetry {
+    fn mul_assign(&mut self, rhs: Symmetry) {
+        *self = *self * rhs;
+    }
+}
+
+/// A plane through the center of the cube, parallel to two opposite faces,
+/// named after the slice lying in it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Plane {
+    /// Between the left and right faces.
+    M,
+    /// Between the up and down faces.
+    E,
+    /// Between the front and back faces.
+    S,
+}
+
+impl Plane {
+    /// The reflection through the plane, swapping the two faces parallel to it.
+    ///
+    /// ```
+    /// use cubie::symmetry::Plane;
+    /// use cubie::{Face, Symmetry};
+    /// assert_eq!(Plane::M.symmetry(), Symmetry::MIRROR);
+    /// assert_eq!(Plane::E.symmetry().face(Face::Up), Face::Down);
+    /// assert_eq!(Plane::E.symmetry().face(Face::Right), Face::Right);
+    /// ```
+    pub fn symmetry(self) -> Symmetry {
+        // A reflection through M followed by a half turn about the axis
+        // shared by both planes.
+        match self {
+            Plane::M => Symmetry::MIRROR,
+            Plane::E => Symmetry::new(Move::Z2.centers(), true),
+            Plane::S => Symmetry::new(Move::Y2.centers(), true),
+        }
+    }
+}
+
+fn mirror_face(face: Face) -> Face {
+    match face {
+        Face::Right => Face::Left,
+        Face::Left => Face::Right,
+        face => face,
+    }
+}
+
+fn mirror_corners(corners: CornerMap) -> CornerMap {
+    let mut raw = 0;
+    for i in 0..8 {
+        let entry = corners.raw >> (i * 8);
+        let position = mirror_corner(entry & 0b111);
+        let twist = (3 - ((entry >> 3) & 0b11)) % 3;
+        raw |= (position | (twist << 3)) << (mirror_corner(i) * 8);
+    }
+    CornerMap { raw }
+}
+
+fn mirror_edges(edges: EdgeMap) -> EdgeMap {
+    let mut raw = 0;
+    for (i, &mirrored) in MIRROR_EDGE.iter().enumerate() {
+        let entry = edges.raw >> (i * 5);
+        let position = MIRROR_EDGE[(entry & 0b1111) as usize] as u64;
+        raw |= (position | (entry & 0b10000)) << (mirrored as u64 * 5);
+    }
+    EdgeMap { raw }
+}
+
+fn mirror_centers(centers: CenterMap) -> CenterMap {
+    let mirrored = |face| mirror_face(centers.get(mirror_face(face)));
+    ROTATION_TABLE
+        .iter()
+        .map(|rotation| rotation.centers())
+        .find(|candidate| Face::faces().all(|face| candidate.get(face) == mirrored(face)))
+        .unwrap()
+}
+
+/// Reflects a cube state through the M plane.
+fn mirror(cube: Cube) -> Cube {
+    Cube::new(
+        mirror_centers(cube.centers()),
+        mirror_corners(cube.corners()),
+        mirror_edges(cube.edges()),
+    )
+}
+
+impl Cube {
+    /// Conjugates the cube state by a symmetry.
+    ///
+    /// For a rotation `r` this is `r.inverse() * self * r`, so the conjugate
+    /// of an algorithm is that algorithm performed from another angle. For
+    /// reflections the state is mirrored left to right first.
+    ///
+    /// # Example
+    /// ```
+    /// use cubie::{Move::*, Symmetry};
+    /// for sym in Symmetry::symmetries() {
+    ///     assert_eq!(R1.cube().conjugate(sym), R1.conjugate(sym).cube());
+    /// }
+    /// ```
+    pub fn conjugate(self, sym: Symmetry) -> Cube {
+        let cube = if sym.is_reflection() {
+            mirror(self)
+        } else {
+            self
+        };
+        let rotation = sym.rotation_cube();
+        rotation.inverse_multiply(cube) * rotation
+    }
+}
+
+impl FixedCentersCube {
+    /// Conjugates the state by a symmetry, see [Cube::conjugate].
+    pub fn conjugate(self, sym: Symmetry) -> FixedCentersCube {
+        Cube::from(self).conjugate(sym).into()
+    }
+}
+
+/// The group of transformations two states are considered equivalent under,
+/// see [Cube::canonical].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Equivalence {
+    /// The 24 whole-cube rotations.
+    Rotations,
+    /// All 48 symmetries, rotations and reflections.
+    Symmetries,
+    /// All 48 symmetries, each optionally combined with inverting the state.
+    SymmetriesAndInverse,
+}
+
+impl Equivalence {
+    fn transformations(self) -> impl Iterator<Item = Transformation> {
+        let (symmetries, inversions) = match self {
+            Equivalence::Rotations => (24, 1),
+            Equivalence::Symmetries => (48, 1),
+            Equivalence::SymmetriesAndInverse => (48, 2),
+        };
+        (0..inversions).flat_map(move |inverse| {
+            (0..symmetries).map(move |index| Transformation {
+                symmetry: Symmetry(index),
+                inverse: inverse == 1,
+            })
+        })
+    }
+}
+
+/// A transformation between equivalent states: an optional inversion
+/// followed by conjugation by a symmetry.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Transformation {
+    /// The symmetry the state is conjugated by.
+    pub symmetry: Symmetry,
+    /// Whether the state is inverted before conjugation.
+    pub inverse: bool,
+}
+
+impl Transformation {
+    /// Applies the transformation to a cube state.
+    pub fn apply(self, cube: Cube) -> Cube {
+        let cube = if self.inverse { cube.inverse() } else { cube };
+        cube.conjugate(self.symmetry)
+    }
+
+    /// The transformation undoing `self`.
+    pub fn inverse(self) -> Transformation {
+        Transformation {
+            symmetry: self.symmetry.inverse(),
+            inverse: self.inverse,
+        }
+    }
+}
+
+impl Cube {
+    /// Finds the representative of the state's equivalence class with the
+    /// smallest [Cube::raw] value, along with the transformation mapping
+    /// `self` onto it.
+    ///
+    /// Two states have the same canonical form exactly when they are
+    /// equivalent, which makes this suitable for deduplicating positions.
+    ///
+    /// # Example
+    /// ```
+    /// use cubie::{Cube, Move::*};
+    /// use cubie::symmetry::Equivalence;
+    ///
+    /// let sune = Cube::from(&[R1, U1, R3, U1, R1, U2, R3]);
+    /// let left_sune = Cube::from(&[L3, U3, L1, U3, L3, U2, L1]);
+    /// let (canonical, transformation) = sune.canonical(Equivalence::Symmetries);
+    /// assert_eq!(transformation.apply(sune), canonical);
+    /// assert_eq!(left_sune.canonical(Equivalence::Symmetries).0, canonical);
+    /// ```
+    pub fn canonical(self, equivalence: Equivalence) -> (Cube, Transformation) {
+        let states = [self, self.inverse()];
+        let mirrored = [mirror(states[0]), mirror(states[1])];
+        equivalence
+            .transformations()
+            .map(|transformation| {
+                let sym = transformation.symmetry;
+                let cube = if sym.is_reflection() {
+                    mirrored[transformation.inverse as usize]
+                } else {
+                    states[transformation.inverse as usize]
+                };
+                let rotation = sym.rotation_cube();
+                (rotation.inverse_multiply(cube) * rotation, transformation)
+            })
+            .min_by_key(|(cube, _)| cube.raw())
+            .unwrap()
+    }
+}
+
+impl FixedCentersCube {
+    /// Finds the canonical form of the state, see [Cube::canonical].
+    pub fn canonical(self, equivalence: Equivalence) -> (FixedCentersCube, Transformation) {
+        let (cube, transformation) = Cube::from(self).canonical(equivalence);
+        (cube.into(), transformation)
+    }
+}
+
+impl Move {
+    /// Conjugates the move by a symmetry, giving the move with the same
+    /// effect as [Cube::conjugate].
+    ///
+    /// ```
+    /// use cubie::{Move::*, Symmetry};
+    /// assert_eq!(R1.conjugate(Symmetry::MIRROR), L3);
+    /// assert_eq!(M1.conjugate(Symmetry::MIRROR), M1);
+    /// assert_eq!(Y1.conjugate(Symmetry::MIRROR), Y3);
+    /// ```
+    pub fn conjugate(self, sym: Symmetry) -> Move {
+        let angle = if sym.is_reflection() {
+            self.angle().inverse()
+        } else {
+            self.angle()
+        };
+        Move::new(self.kind(), sym.face(self.face()), angle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Move::*;
+
+    #[test]
+    fn identity_and_mirror() {
+        let mut rng = oorandom::Rand32::new(4);
+        for _ in 0..20 {
+            let cube = Cube::random(&mut rng);
+            assert_eq!(cube.conjugate(Symmetry::IDENTITY), cube);
+            assert_eq!(mirror(mirror(cube)), cube);
+            assert!(mirror(cube).has_solution());
+        }
+        assert_eq!(Symmetry::IDENTITY.rotation(), CenterMap::default());
+        assert_eq!(Symmetry::IDENTITY.index(), 0);
+        assert_eq!(Symmetry::MIRROR.index(), 24);
+        assert_eq!(Symmetry::new(CenterMap::default(), true), Symmetry::MIRROR);
+        for sym in Symmetry::symmetries() {
+            assert_eq!(Symmetry::new(sym.rotation(), sym.is_reflection()), sym);
+        }
+        assert_eq!(R1.cube().conjugate(Symmetry::MIRROR), L3.cube());
+        assert_eq!(U1.cube().conjugate(Symmetry::MIRROR), U3.cube());
+    }
+
+    #[test]
+    fn plane_reflections() {
+        use Face::*;
+        let swaps = [
+            (Plane::M, Left, Right),
+            (Plane::E, Up, Down),
+            (Plane::S, Front, Back),
+        ];
+        for &(plane, a, b) in &swaps {
+            let sym = plane.symmetry();
+            assert!(sym.is_reflection());
+            assert_eq!(sym * sym, Symmetry::IDENTITY);
+            for face in Face::faces() {
+                let expected = match face {
+                    face if face == a => b,
+                    face if face == b => a,
+                    face => face,
+                };
+                assert_eq!(sym.face(face), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn conjugation_is_homomorphism() {
+        let mut rng = oorandom::Rand32::new(8);
+        for sym in Symmetry::symmetries() {
+            let (a, b) = (Cube::random(&mut rng), Cube::random(&mut rng));
+            assert_eq!((a * b).conjugate(sym), a.conjugate(sym) * b.conjugate(sym));
+            assert_eq!(a.conjugate(sym).conjugate(sym.inverse()), a);
+            assert_eq!(sym * sym.inverse(), Symmetry::IDENTITY);
+            assert_eq!(sym.inverse() * sym, Symmetry::IDENTITY);
+            for other in Symmetry::symmetries() {
+                assert_eq!(a.conjugate(sym).conjugate(other), a.conjugate(sym * other));
+            }
+        }
+    }
+
+    #[test]
+    fn moves_match_cubes() {
+        for sym in Symmetry::symmetries() {
+            for mv in Move::moves() {
+                assert_eq!(mv.cube().conjugate(sym), mv.conjugate(sym).cube());
+            }
+        }
+    }
+
+    #[test]
+    fn canonical_forms() {
+        let mut rng = oorandom::Rand32::new(12);
+        for _ in 0..10 {
+            let cube = Cube::random(&mut rng);
+            for &equivalence in &[
+                Equivalence::Rotations,
+                Equivalence::Symmetries,
+                Equivalence::SymmetriesAndInverse,
+            ] {
+                let (canonical, transformation) = cube.canonical(equivalence);
+                assert_eq!(transformation.apply(cube), canonical);
+                assert_eq!(transformation.inverse().apply(canonical), cube);
+                for other in equivalence.transformations() {
+                    let equivalent = other.apply(cube);
+                    assert!(equivalent.raw() >= canonical.raw());
+                    assert_eq!(equivalent.canonical(equivalence).0, canonical);
+                }
+            }
+        }
+        let mirrored = R1.cube().canonical(Equivalence::Rotations).0;
+        assert_ne!(L3.cube().canonical(Equivalence::Rotations).0, mirrored);
+        assert_eq!(
+            L3.cube().canonical(Equivalence::Symmetries).0,
+            R1.cube().canonical(Equivalence::Symmetries).0
+        );
+    }
+
+    #[test]
+    fn symmetries_are_distinct() {
+        let cube = Cube::from(&[R1, U2, F3, D1, L2, B1]);
+        let mut seen = std::collections::HashSet::new();
+        for sym in Symmetry::symmetries() {
+            assert!(seen.insert(cube.conjugate(sym)));
+        }
+    }
+}
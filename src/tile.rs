@@ -16,6 +16,20 @@ pub enum TileMapConversionError {
     Edge(MapError),
 }
 
+impl std::fmt::Display for TileMapConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TMErr::InvalidPiece => f.write_str("tiles do not form a valid piece"),
+            TMErr::MissingTile => f.write_str("missing tile"),
+            TMErr::Corner(err) => write!(f, "invalid corners: {}", err),
+            TMErr::Center(err) => write!(f, "invalid centers: {}", err),
+            TMErr::Edge(err) => write!(f, "invalid edges: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for TileMapConversionError {}
+
 #[rustfmt::skip]
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Tile {
@@ -27,6 +41,44 @@ pub enum Tile {
     L1, L2, L3, L4, L5, L6, L7, L8, L9,
 }
 
+impl std::convert::TryFrom<u8> for Tile {
+    type Error = &'static str;
+    #[inline]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        if value >= 54 {
+            Err("Value too large (>53).")
+        } else {
+            unsafe { Ok(std::mem::transmute::<u8, Tile>(value)) }
+        }
+    }
+}
+
+/// Formats the tile as its face symbol followed by its index on the face,
+/// e.g. `U1`.
+impl std::fmt::Display for Tile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+impl std::str::FromStr for Tile {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let face: Face = match chars.next() {
+            Some(symbol) => symbol.encode_utf8(&mut [0; 4]).parse()?,
+            None => return Err("Missing face symbol."),
+        };
+        match (chars.next().and_then(|c| c.to_digit(10)), chars.next()) {
+            (Some(index @ 1..=9), None) => {
+                use std::convert::TryFrom;
+                Tile::try_from(face as u8 * 9 + index as u8 - 1)
+            }
+            _ => Err("Tile index must be a digit from 1 to 9."),
+        }
+    }
+}
+
 use std::ops::{Index, IndexMut};
 use TileMapConversionError as TMErr;
 impl Index<Tile> for TileMap {
@@ -88,6 +140,65 @@ impl From<EdgeMap> for TileMap {
     }
 }
 
+/// Formats the tile map as a 54 character facelet string.
+///
+/// This is the Kociemba facelet order of [TileMap::to_facelets]: face by
+/// face in the order U, R, F, D, L, B, each as 9 tiles read row by row, each
+/// tile as the symbol of the face its sticker belongs to, or `.` for a
+/// missing tile.
+///
+/// # Example
+/// ```
+/// use cubie::{Cube, TileMap};
+/// let tiles = TileMap::from(Cube::default());
+/// let text = tiles.to_string();
+/// assert!(text.starts_with("UUUUUUUUURRRRRRRRR"));
+/// assert_eq!(text.parse::<TileMap>(), Ok(tiles));
+/// ```
+impl std::fmt::Display for TileMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for &face in KOCIEMBA_FACE_ORDER.iter() {
+            for tile in self.map[face as usize].iter() {
+                match tile {
+                    Some(color) => write!(f, "{}", color)?,
+                    None => f.write_str(".")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses the facelet string produced by the [Display](std::fmt::Display)
+/// implementation. Whitespace is ignored.
+///
+/// Unlike [TileMap::from_facelets], the symbols must be face symbols, so
+/// maps with turned centers or missing tiles can be read back.
+impl std::str::FromStr for TileMap {
+    type Err = TileMapConversionError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tilemap = TileMap::default();
+        let mut symbols = s.chars().filter(|c| !c.is_whitespace());
+        for &face in KOCIEMBA_FACE_ORDER.iter() {
+            for tile in tilemap.map[face as usize].iter_mut() {
+                *tile = match symbols.next().ok_or(TMErr::MissingTile)? {
+                    '.' => None,
+                    symbol => Some(
+                        symbol
+                            .encode_utf8(&mut [0; 4])
+                            .parse()
+                            .map_err(|_| TMErr::InvalidPiece)?,
+                    ),
+                };
+            }
+        }
+        if symbols.next().is_some() {
+            return Err(TMErr::InvalidPiece);
+        }
+        Ok(tilemap)
+    }
+}
+
 use crate::cube::edge::EdgeOrientation;
 fn edge_from_faces(a: Face, b: Face) -> Option<(Edge, EdgeOrientation)> {
     use Edge::*;
@@ -164,6 +275,16 @@ pub fn tiles_from_corner(corner: Corner) -> [usize; 3] {
 
 use crate::cube::corner::CornerOrientation;
 
+/// The order faces are listed in Kociemba facelet strings.
+const KOCIEMBA_FACE_ORDER: [Face; 6] = [
+    Face::Up,
+    Face::Right,
+    Face::Front,
+    Face::Down,
+    Face::Left,
+    Face::Back,
+];
+
 impl TileMap {
     #[doc(hidden)]
     pub fn as_array(&self) -> &[Option<Face>; 54] {
@@ -275,7 +396,7 @@ impl TileMap {
                     .iter()
                     .map(|m| tiles[*m])
                     .zip(new.iter())
-                    .all(|(o, n)| o.is_none_or(|a| a == *n))
+                    .all(|(o, n)| o.map_or(true, |a| a == *n))
                 {
                     for i in 0..3 {
                         tiles[map[i]] = Some(new[i]);
@@ -416,6 +537,65 @@ impl TileMap {
         CornerMap::from_raw(raw).map_err(TMErr::Corner)
     }
 
+    /// Parses a Kociemba facelet string, the 54 character format used by
+    /// Kociemba's solver, Cube Explorer and many cube robots.
+    ///
+    /// Faces are listed in the order U, R, F, D, L, B, each as 9 stickers
+    /// read row by row. Any six distinct symbols may be used for the stickers:
+    /// the face of each symbol is inferred from the center it appears on.
+    /// The resulting map therefore always has its centers in the standard
+    /// orientation.
+    ///
+    /// # Errors
+    /// - [TileMapConversionError::MissingTile] if the string is not 54
+    ///   characters long.
+    /// - [TileMapConversionError::Center] if two centers share a symbol.
+    /// - [TileMapConversionError::InvalidPiece] if a symbol does not appear
+    ///   on any center.
+    ///
+    /// # Example
+    /// ```
+    /// use cubie::{Cube, Move::*, TileMap};
+    /// let solved = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+    /// assert!(TileMap::from_facelets(solved).unwrap().cube().unwrap().is_solved());
+    ///
+    /// let cube = Cube::from(&[R1, U1, F3]);
+    /// let facelets = TileMap::from(cube).to_facelets();
+    /// assert_eq!(TileMap::from_facelets(&facelets).unwrap().cube(), Ok(cube));
+    /// ```
+    pub fn from_facelets(facelets: &str) -> Result<TileMap, TMErr> {
+        let symbols: Vec<char> = facelets.chars().collect();
+        if symbols.len() != 54 {
+            return Err(TMErr::MissingTile);
+        }
+        let mut faces = Vec::with_capacity(6);
+        for (&face, stickers) in KOCIEMBA_FACE_ORDER.iter().zip(symbols.chunks(9)) {
+            if faces.iter().any(|&(symbol, _)| symbol == stickers[4]) {
+                return Err(TMErr::Center(MapError::Duplicate));
+            }
+            faces.push((stickers[4], face));
+        }
+        let mut tilemap = TileMap::default();
+        for (&face, stickers) in KOCIEMBA_FACE_ORDER.iter().zip(symbols.chunks(9)) {
+            for (tile, sticker) in tilemap.map[face as usize].iter_mut().zip(stickers) {
+                let (_, color) = faces
+                    .iter()
+                    .find(|(symbol, _)| symbol == sticker)
+                    .ok_or(TMErr::InvalidPiece)?;
+                *tile = Some(*color);
+            }
+        }
+        Ok(tilemap)
+    }
+
+    /// Formats the tile map as a Kociemba facelet string, see
+    /// [TileMap::from_facelets]. Missing tiles are written as `.`.
+    /// This is the same string as the [Display](std::fmt::Display)
+    /// implementation.
+    pub fn to_facelets(&self) -> String {
+        self.to_string()
+    }
+
     #[doc(hidden)]
     pub fn store_identity_centers(&mut self) {
         for face in Face::faces() {
@@ -581,4 +761,47 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn kociemba_facelets() {
+        use crate::Move::*;
+        // Superflip in Kociemba's own format.
+        let superflip = "UBULURUFURURFRBRDRFUFLFRFDFDFDLDRDBDLULBLFLDLBUBRBLBDB";
+        let cube = TileMap::from_facelets(superflip).unwrap().cube().unwrap();
+        let expected = Cube::from(&[
+            U1, R2, F1, B1, R1, B2, R1, U2, L1, B2, R1, U3, D3, R2, F1, R3, L1, B2, U2, F2,
+        ]);
+        assert_eq!(cube, expected);
+        assert_eq!(TileMap::from(cube).to_facelets(), superflip);
+        assert_eq!(TileMap::from(cube).to_string(), superflip);
+        assert_eq!(superflip.parse(), Ok(TileMap::from(cube)));
+
+        // Arbitrary colour symbols are mapped through the centers.
+        let colors: String = superflip
+            .chars()
+            .map(|c| match c {
+                'U' => 'W',
+                'R' => 'R',
+                'F' => 'G',
+                'D' => 'Y',
+                'L' => 'O',
+                _ => 'B',
+            })
+            .collect();
+        assert_eq!(TileMap::from_facelets(&colors).unwrap().cube(), Ok(cube));
+
+        assert_eq!(
+            TileMap::from_facelets(&superflip[1..]),
+            Err(TMErr::MissingTile)
+        );
+        let mut duplicate: Vec<char> = superflip.chars().collect();
+        duplicate[13] = 'U';
+        let duplicate: String = duplicate.into_iter().collect();
+        assert_eq!(
+            TileMap::from_facelets(&duplicate),
+            Err(TMErr::Center(MapError::Duplicate))
+        );
+        let unknown = superflip.replacen('B', "X", 1);
+        assert_eq!(TileMap::from_facelets(&unknown), Err(TMErr::InvalidPiece));
+    }
 }